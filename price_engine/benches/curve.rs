@@ -0,0 +1,34 @@
+// Serial vs rayon pricing of a 1000-point strike curve. Run with `cargo bench --features rayon`.
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use price_engine::{BlackScholes, DayCount, OptionParams, OptionStyle, PricingModel};
+use std::hint::black_box;
+
+const CURVE_POINTS: usize = 1000;
+
+fn curve_benchmark(c: &mut Criterion) {
+    let params = OptionParams {
+        underlying_price: 2.0,
+        strike_price: 2.0,
+        time_to_expiry: 30.0,
+        volatility: 0.8,
+        risk_free_rate: 0.05,
+        is_call: true,
+        style: OptionStyle::European,
+        day_count: DayCount::default(),
+        model: PricingModel::default(),
+    };
+    let strikes: Vec<f64> = (0..CURVE_POINTS).map(|i| 1.0 + i as f64 * 0.002).collect();
+
+    let mut group = c.benchmark_group("premium_curve");
+    group.throughput(Throughput::Elements(CURVE_POINTS as u64));
+    group.bench_with_input(BenchmarkId::new("serial", CURVE_POINTS), &strikes, |b, strikes| {
+        b.iter(|| BlackScholes::calculate_premiums(black_box(&params), black_box(strikes)).unwrap())
+    });
+    group.bench_with_input(BenchmarkId::new("parallel", CURVE_POINTS), &strikes, |b, strikes| {
+        b.iter(|| BlackScholes::calculate_premiums_par(black_box(&params), black_box(strikes)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, curve_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+// Baseline pricing throughput. Run with `cargo bench --bench pricing`.
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use price_engine::{
+    BlackScholes, DayCount, EngineConfig, MockOracle, OptionParams, OptionStyle, OptionsPricingEngine, PricingModel,
+};
+use std::hint::black_box;
+use std::sync::Arc;
+
+// Calm, typical crypto and stressed markets
+const VOL_REGIMES: [(&str, f64); 3] = [("low", 0.2), ("mid", 0.8), ("high", 1.5)];
+
+fn params(volatility: f64, is_call: bool) -> OptionParams {
+    OptionParams {
+        underlying_price: 2.0,
+        strike_price: 2.1,
+        time_to_expiry: 30.0,
+        volatility,
+        risk_free_rate: 0.05,
+        is_call,
+        style: OptionStyle::European,
+        day_count: DayCount::default(),
+        model: PricingModel::default(),
+    }
+}
+
+fn premium_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_premium");
+    group.throughput(Throughput::Elements(1));
+    for (regime, vol) in VOL_REGIMES {
+        for (kind, is_call) in [("call", true), ("put", false)] {
+            let params = params(vol, is_call);
+            group.bench_with_input(BenchmarkId::new(kind, regime), &params, |b, params| {
+                b.iter(|| BlackScholes::calculate_premium(black_box(params)).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+fn greeks_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_greeks");
+    group.throughput(Throughput::Elements(1));
+    for (regime, vol) in VOL_REGIMES {
+        for (kind, is_call) in [("call", true), ("put", false)] {
+            let params = params(vol, is_call);
+            group.bench_with_input(BenchmarkId::new(kind, regime), &params, |b, params| {
+                b.iter(|| BlackScholes::calculate_greeks(black_box(params)).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+fn curve_benchmark(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.8));
+    let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+
+    // 1.0..=3.0 by 0.01 is 201 strikes, each priced as a call and a put
+    let mut group = c.benchmark_group("calculate_premium_curve");
+    group.throughput(Throughput::Elements(402));
+    group.bench_function("mock_oracle", |b| {
+        b.iter(|| {
+            runtime
+                .block_on(engine.calculate_premium_curve("SUI", 30, black_box((1.0, 3.0, 0.01))))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, premium_benchmark, greeks_benchmark, curve_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+use futures::stream::StreamExt; // For stream processing methods
+use pyth_hermes_client::PythClient;
+use anyhow::Result;
+
+// Streams SUI/USD updates from Hermes and prints every parsed price.
+//
+//     cargo run --example stream_prices
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Create a new Pyth client
+    let client = PythClient::new(
+        "https://hermes.pyth.network".parse().unwrap()
+    );
+
+    // SUI/USD price feed ID
+    let sui_price_id: String = String::from("0x23d7315113f5b1d3ba7a83604c44b94d79f4fd69af77f804fc7f920a6dc65744");
+    let v: Vec<String> = vec![sui_price_id.clone()];
+    // Subscribe to price updates
+    let mut price_updates = client.stream_price_updates(v, None, Some(true), Some(false), Some(true)).await?;
+
+    // Process the stream
+    while let Some(update) = price_updates.next().await {
+        match update {
+            Ok(price_update) => {
+                println!("Received price update:");
+
+                // Access parsed data if available
+                if let Some(parsed) = &price_update.parsed {
+                    for price_feed in parsed {
+                        let price = price_feed.price.price as f64 * 10.0_f64.powi(price_feed.price.expo);
+                        println!("Symbol: {}, Price: ${:.4}", price_feed.id, price);
+                        println!("Confidence: {}, Timestamp: {}",
+                                 price_feed.price.conf,
+                                 price_feed.price.publish_time);
+                    }
+                }
+            },
+            Err(err) => {
+                eprintln!("Error receiving price update: {:?}", err);
+            }
+        }
+    }
+
+    Ok(())
+}
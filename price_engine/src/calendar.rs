@@ -0,0 +1,176 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+// 1970-01-01 was a Thursday
+const EPOCH_WEEKDAY: i64 = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpirySchedule {
+    /// Every week on the calendar's weekday
+    #[default]
+    Weekly,
+    /// The last of the calendar's weekday in each month
+    Monthly,
+}
+
+/// When listed options expire, e.g. Fridays at 08:00 UTC. Lets a "7 days" request be priced
+/// to the listed expiry nearest to it instead of exactly a week from now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawCalendar")]
+pub struct ExpiryCalendar {
+    pub schedule: ExpirySchedule,
+    // 0 = Sunday ... 6 = Saturday
+    pub weekday: u8,
+    pub hour_utc: u8,
+}
+
+impl Default for ExpiryCalendar {
+    fn default() -> Self {
+        Self { schedule: ExpirySchedule::Weekly, weekday: 5, hour_utc: 8 }
+    }
+}
+
+// The calendar as written in config, checked before it becomes an `ExpiryCalendar`
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawCalendar {
+    schedule: ExpirySchedule,
+    weekday: u8,
+    hour_utc: u8,
+}
+
+impl Default for RawCalendar {
+    fn default() -> Self {
+        let ExpiryCalendar { schedule, weekday, hour_utc } = ExpiryCalendar::default();
+        Self { schedule, weekday, hour_utc }
+    }
+}
+
+impl TryFrom<RawCalendar> for ExpiryCalendar {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawCalendar) -> Result<Self> {
+        let calendar = Self { schedule: raw.schedule, weekday: raw.weekday, hour_utc: raw.hour_utc };
+        calendar.validate()?;
+        Ok(calendar)
+    }
+}
+
+impl ExpiryCalendar {
+    /// Rejects a weekday past 6 (Saturday) or an hour past 23, which would otherwise
+    /// quietly roll over into another day.
+    pub fn validate(&self) -> Result<()> {
+        if self.weekday > 6 {
+            bail!("expiry calendar weekday must be 0 (Sunday) to 6 (Saturday), got {}", self.weekday);
+        }
+        if self.hour_utc > 23 {
+            bail!("expiry calendar hour_utc must be 0 to 23, got {}", self.hour_utc);
+        }
+        Ok(())
+    }
+
+    /// First listed expiry strictly after `after` (UNIX seconds).
+    pub fn next_expiry(&self, after: i64) -> i64 {
+        let mut candidate = self.next_weekly(after);
+        while self.schedule == ExpirySchedule::Monthly && !is_last_in_month(candidate) {
+            candidate += SECONDS_PER_WEEK;
+        }
+        candidate
+    }
+
+    /// The listed expiry nearest to `days` days after `now`, never one at or before `now`.
+    /// Between two listed expiries the closer one wins, the later one on a tie.
+    pub fn snap(&self, now: i64, days: u32) -> i64 {
+        let target = now + days as i64 * SECONDS_PER_DAY;
+        let mut before = None;
+        let mut after = self.next_expiry(now);
+        while after < target {
+            before = Some(after);
+            after = self.next_expiry(after);
+        }
+        match before {
+            Some(before) if target - before < after - target => before,
+            _ => after,
+        }
+    }
+
+    // Next occurrence of the weekday and hour strictly after `after`
+    fn next_weekly(&self, after: i64) -> i64 {
+        let day = after.div_euclid(SECONDS_PER_DAY);
+        let weekday = (day + EPOCH_WEEKDAY).rem_euclid(7);
+        let days_ahead = (self.weekday as i64 - weekday).rem_euclid(7);
+        let candidate = (day + days_ahead) * SECONDS_PER_DAY + self.hour_utc as i64 * 3600;
+        if candidate > after { candidate } else { candidate + SECONDS_PER_WEEK }
+    }
+}
+
+// Whether a week later falls in another month
+fn is_last_in_month(timestamp: i64) -> bool {
+    month_of(timestamp) != month_of(timestamp + SECONDS_PER_WEEK)
+}
+
+// (year, month) of a UNIX time, from Howard Hinnant's civil_from_days
+fn month_of(timestamp: i64) -> (i64, i64) {
+    let z = timestamp.div_euclid(SECONDS_PER_DAY) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-01 00:00 UTC, a Monday
+    const MONDAY: i64 = 1_704_067_200;
+    // 2024-01-05 08:00 UTC, that week's Friday expiry
+    const FRIDAY: i64 = MONDAY + 4 * SECONDS_PER_DAY + 8 * 3600;
+
+    #[test]
+    fn test_weekly_expiries_snap_to_nearest_friday() {
+        let calendar = ExpiryCalendar::default();
+        assert_eq!(calendar.next_expiry(MONDAY), FRIDAY);
+        // At the expiry itself the next one is a week out
+        assert_eq!(calendar.next_expiry(FRIDAY), FRIDAY + SECONDS_PER_WEEK);
+
+        // Monday + 7 days is under 3 days past one Friday expiry and over 4 before the next
+        assert_eq!(calendar.snap(MONDAY, 7), FRIDAY);
+        assert_eq!(calendar.snap(MONDAY, 10), FRIDAY + SECONDS_PER_WEEK);
+        // A same-day request still gets the next listed expiry
+        assert_eq!(calendar.snap(MONDAY, 0), FRIDAY);
+    }
+
+    #[test]
+    fn test_monthly_expiries_are_last_friday() {
+        let calendar = ExpiryCalendar { schedule: ExpirySchedule::Monthly, ..ExpiryCalendar::default() };
+        // 2024-01-26 and 2024-02-23
+        let january = MONDAY + 25 * SECONDS_PER_DAY + 8 * 3600;
+        let february = january + 4 * SECONDS_PER_WEEK;
+        assert_eq!(calendar.next_expiry(MONDAY), january);
+        assert_eq!(calendar.next_expiry(january), february);
+        assert_eq!(month_of(february), (2024, 2));
+
+        // 35 days out is 2024-02-05: nearer the January expiry than the February one
+        assert_eq!(calendar.snap(MONDAY, 35), january);
+        assert_eq!(calendar.snap(MONDAY, 45), february);
+    }
+
+    #[test]
+    fn test_out_of_range_calendar_is_rejected() {
+        let calendar: ExpiryCalendar = toml::from_str("schedule = \"monthly\"\nhour_utc = 23\n").unwrap();
+        assert_eq!(calendar, ExpiryCalendar { schedule: ExpirySchedule::Monthly, hour_utc: 23, ..ExpiryCalendar::default() });
+
+        let err = toml::from_str::<ExpiryCalendar>("weekday = 9\n").unwrap_err();
+        assert!(err.to_string().contains("weekday must be 0 (Sunday) to 6"), "{}", err);
+        let err = toml::from_str::<ExpiryCalendar>("hour_utc = 30\n").unwrap_err();
+        assert!(err.to_string().contains("hour_utc must be 0 to 23"), "{}", err);
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{at_strike, price_strikes_on};
+use crate::pricing::{BlackScholes, Greeks, Moneyness, OptionParams, classify_strikes};
+use crate::quote::GreeksRounding;
+use crate::smile::VolSmile;
+
+// One side of a chain row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainQuote {
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub premium: f64,
+    pub greeks: Greeks,
+    pub moneyness: Moneyness,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainRow {
+    pub strike: f64,
+    // S / K, as in `StrikeMoneyness`
+    pub moneyness: f64,
+    // Vol both sides were priced at: the smile's at this strike, or the flat vol
+    pub volatility: f64,
+    pub call: ChainQuote,
+    pub put: ChainQuote,
+}
+
+// Calls and puts side by side for one expiry, all priced off one underlying snapshot,
+// one row per strike in ascending order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionChain {
+    pub symbol: String,
+    pub underlying_price: f64,
+    // Days the chain was priced to: to the listed expiry, after the time-to-expiry floor
+    pub days_to_expiry: f64,
+    pub rows: Vec<ChainRow>,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub from_cache: bool,
+}
+
+impl OptionChain {
+    // Prices every strike from `base`, which carries the underlying, vol, rate and expiry;
+    // a smile overrides the vol strike by strike
+    pub(crate) fn price(
+        base: &OptionParams,
+        strikes: &[f64],
+        smile: Option<&VolSmile>,
+        atm_band: f64,
+    ) -> Result<Self> {
+        let call = OptionParams { is_call: true, ..base.clone() };
+        let put = OptionParams { is_call: false, ..base.clone() };
+        let call_premiums = price_strikes_on(&call, strikes, smile)?;
+        let put_premiums = price_strikes_on(&put, strikes, smile)?;
+
+        let rows = classify_strikes(base.underlying_price, strikes, atm_band).into_iter()
+            .zip(call_premiums.into_iter().zip(put_premiums))
+            .map(|(labels, (call_premium, put_premium))| {
+                let call = at_strike(&call, labels.strike, smile);
+                let put = at_strike(&put, labels.strike, smile);
+                Ok(ChainRow {
+                    strike: labels.strike,
+                    moneyness: labels.moneyness,
+                    volatility: call.volatility,
+                    call: ChainQuote {
+                        premium: call_premium,
+                        greeks: BlackScholes::calculate_greeks(&call)?,
+                        moneyness: labels.call,
+                    },
+                    put: ChainQuote {
+                        premium: put_premium,
+                        greeks: BlackScholes::calculate_greeks(&put)?,
+                        moneyness: labels.put,
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            symbol: String::new(),
+            underlying_price: base.underlying_price,
+            days_to_expiry: base.time_to_expiry,
+            rows,
+            timestamp: 0,
+            from_cache: false,
+        })
+    }
+
+    /// The row struck at exactly `strike`, if the chain has one.
+    pub fn row(&self, strike: f64) -> Option<&ChainRow> {
+        self.rows.iter().find(|row| row.strike == strike)
+    }
+
+    /// Mean of the rows' vols weighted by their call plus put vega, the desk's one-number
+    /// summary of a chain. Rows without vega don't count; fails when no row has any.
+    pub fn vega_weighted_vol(&self) -> Result<f64> {
+        let (weighted, total) = self.rows.iter()
+            .map(|row| (row.volatility, row.call.greeks.vega + row.put.greeks.vega))
+            .filter(|&(_, vega)| vega > 0.0)
+            .fold((0.0, 0.0), |(weighted, total), (vol, vega)| (weighted + vol * vega, total + vega));
+        if total == 0.0 {
+            bail!("No strike in the {} chain has any vega to weight by", self.symbol);
+        }
+        Ok(weighted / total)
+    }
+
+    /// Rounds every row's Greeks by `rounding`, for a chain that is about to be served.
+    pub fn round_greeks(&mut self, rounding: &GreeksRounding) {
+        for row in &mut self.rows {
+            row.call.greeks = rounding.apply(row.call.greeks);
+            row.put.greeks = rounding.apply(row.put.greeks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::{DayCount, OptionStyle, PricingModel};
+
+    #[test]
+    fn test_vega_weighted_vol() {
+        let base = OptionParams {
+            underlying_price: 2.0,
+            strike_price: 2.0,
+            time_to_expiry: 30.0,
+            risk_free_rate: 0.05,
+            volatility: 0.5,
+            is_call: true,
+            day_count: DayCount::Act365,
+            style: OptionStyle::European,
+            model: PricingModel::BlackScholesSpot,
+        };
+        let strikes = [1.0, 1.5, 2.0, 2.5, 3.0];
+        let flat = OptionChain::price(&base, &strikes, None, 0.02).unwrap();
+        assert!((flat.vega_weighted_vol().unwrap() - 0.5).abs() < 1e-12);
+
+        // The ATM strike carries the most vega, so the mean leans toward its vol
+        let smile = VolSmile::new(vec![(1.0, 0.9), (2.0, 0.5), (3.0, 0.9)]).unwrap();
+        let smiled = OptionChain::price(&base, &strikes, Some(&smile), 0.02).unwrap();
+        let mean = smiled.rows.iter().map(|row| row.volatility).sum::<f64>() / strikes.len() as f64;
+        let weighted = smiled.vega_weighted_vol().unwrap();
+        assert!(weighted > 0.5 && weighted < mean, "{} vs plain mean {}", weighted, mean);
+
+        // Expired: no vega anywhere
+        let expired = OptionChain::price(&OptionParams { time_to_expiry: 0.0, ..base.clone() }, &strikes, None, 0.02).unwrap();
+        assert!(expired.vega_weighted_vol().is_err());
+        assert!(OptionChain::price(&base, &[], None, 0.02).unwrap().vega_weighted_vol().is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Where the engine reads "now" from: price staleness, expiries given as timestamps, the
+/// timestamps on results and health reports, premium cache expiry and the fetch dedup window.
+/// Swap in a `MockClock` to make those deterministic in tests.
+pub trait Clock: Send + Sync {
+    /// Current UNIX time in seconds.
+    fn now_secs(&self) -> u64;
+
+    /// Current UNIX time in milliseconds, for windows shorter than a second.
+    fn now_millis(&self) -> u64 {
+        self.now_secs() * 1000
+    }
+}
+
+/// The system's wall clock, what the engine uses unless told otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_secs(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn now_millis(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now_millis: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new(now_secs: u64) -> Self {
+        Self { now_millis: AtomicU64::new(now_secs * 1000) }
+    }
+
+    pub fn set(&self, now_secs: u64) {
+        self.now_millis.store(now_secs * 1000, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now_millis.fetch_add(by.as_millis() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_secs(&self) -> u64 {
+        self.now_millis() / 1000
+    }
+
+    fn now_millis(&self) -> u64 {
+        self.now_millis.load(Ordering::SeqCst)
+    }
+}
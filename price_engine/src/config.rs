@@ -0,0 +1,46 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+// Deserializes a config file as JSON when it has a `.json` extension, TOML otherwise.
+// Errors name the offending field, e.g. `config.toml: invalid value for `risk_free_rate`: ...`
+pub fn load<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T> {
+    let path = path.as_ref();
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+    let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if is_json {
+        let mut deserializer = serde_json::Deserializer::from_str(&raw);
+        serde_path_to_error::deserialize(&mut deserializer)
+            .map_err(|err| field_error(path, err.path(), err.inner()))
+    } else {
+        let deserializer = toml::Deserializer::parse(&raw)
+            .with_context(|| format!("Malformed TOML in {}", path.display()))?;
+        serde_path_to_error::deserialize(deserializer)
+            .map_err(|err| field_error(path, err.path(), err.inner()))
+    }
+}
+
+fn field_error(path: &Path, field: &serde_path_to_error::Path, err: &dyn std::fmt::Display) -> anyhow::Error {
+    anyhow::anyhow!("{}: invalid value for `{}`: {}", path.display(), field, err)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Writes `contents` to a uniquely named file in the system temp dir
+    pub(crate) fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("price_engine_{}_{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_missing_file_errors() {
+        let err = load::<toml::Table>("/nonexistent/price_engine.toml").unwrap_err();
+        assert!(err.to_string().contains("Failed to read config file"));
+    }
+}
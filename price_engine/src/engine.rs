@@ -0,0 +1,3094 @@
+use anyhow::{Context, Result};
+use futures::FutureExt;
+use futures::future::{self, BoxFuture, Shared};
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, instrument, warn};
+
+use crate::calendar::ExpiryCalendar;
+use crate::clock::{Clock, SystemClock};
+use crate::chain::OptionChain;
+use crate::config;
+use crate::error::EngineError;
+#[cfg(feature = "history")]
+use crate::history::PriceHistory;
+use crate::metrics::metrics;
+use crate::oracle::{HealthReport, PriceData, PriceOracle};
+use crate::pricing::{
+    BlackScholes, DayCount, DaysToExpiry, Expiry, Greeks, OptionParams, OptionStyle, PricingModel, StrikeMoneyness, breakeven,
+    check_premium_bounds, classify_strikes, invalid_param,
+};
+use crate::quote::{GreeksRounding, Settlement};
+use crate::rates::{ConstantRate, HttpRateConfig, HttpRateProvider, RateCurve, RateProvider};
+#[cfg(feature = "history")]
+use crate::realized::VolEstimator;
+use crate::retry::{RetryPolicy, is_retryable, retry};
+use crate::smile::VolSmile;
+use crate::portfolio::{Portfolio, PortfolioGreeks};
+use crate::strategy::{LegPremium, Strategy, StrategyResult};
+use crate::streamer::{PriceStreamer, PriceSubscription};
+use crate::surface::Surface;
+
+// Serializing fails on a NaN or infinite number rather than writing a `null` that can't be read back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PremiumResult {
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub strike: f64,
+    pub is_call: bool,
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub premium: f64,
+    // Risk-neutral chance of expiring in the money, in [0, 1]
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub prob_itm: f64,
+    // Underlying price at expiry where a long position breaks even
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub breakeven: f64,
+    // Premium divided by spot, i.e. in units of the underlying; set when `quote_in_underlying` is on
+    #[serde(default, skip_serializing_if = "Option::is_none", serialize_with = "crate::finite::option::serialize")]
+    pub premium_in_underlying: Option<f64>,
+    // Premium for one contract: `premium` times the symbol's `contract_multiplier`
+    #[serde(default, serialize_with = "crate::finite::serialize")]
+    pub notional_premium: f64,
+    pub timestamp: u64,
+    // true when the oracle was unreachable and a recent cached price was used instead
+    #[serde(default)]
+    pub from_cache: bool,
+    // Where the vol came from; `Default` means the oracle had none and the quote is a guess
+    #[serde(default)]
+    pub vol_source: VolSource,
+    // Exactly what was priced: underlying, vol, rate and time as the model saw them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<OptionParams>,
+}
+
+impl PremiumResult {
+    /// The premium to `precision` decimals, followed by the underlying-denominated quote if there is one.
+    pub fn formatted(&self, precision: usize) -> String {
+        match self.premium_in_underlying {
+            Some(quote) => format!("${:.p$} ({:.p$} in underlying)", self.premium, quote, p = precision),
+            None => format!("${:.p$}", self.premium, p = precision),
+        }
+    }
+
+    /// Prices `inputs` again, e.g. to check a stored quote; None when the result has no inputs.
+    pub fn recompute(&self) -> Option<Result<f64>> {
+        self.inputs.as_ref().map(BlackScholes::calculate_premium)
+    }
+
+    /// Same symbol, strike and option type, with premiums no more than `epsilon` apart.
+    pub fn approx_eq(&self, other: &PremiumResult, epsilon: f64) -> bool {
+        self.symbol == other.symbol
+            && self.strike == other.strike
+            && self.is_call == other.is_call
+            && (self.premium - other.premium).abs() <= epsilon
+    }
+}
+
+// `SUI Call K=2.89 prem=$0.12`; the formatter's precision (default 2) applies to the premium
+impl fmt::Display for PremiumResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.is_call { "Call" } else { "Put" };
+        let precision = f.precision().unwrap_or(DEFAULT_DISPLAY_PRECISION);
+        if !self.symbol.is_empty() {
+            write!(f, "{} ", self.symbol)?;
+        }
+        write!(f, "{} K={} prem=${:.p$}", kind, self.strike, self.premium, p = precision)
+    }
+}
+
+// Every field is optional in a config file; missing ones keep their default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngineConfig {
+    // Continuously compounded; negative rates are fine
+    pub risk_free_rate: f64,
+    pub default_volatility: f64,
+    // What to price at when the oracle's volatility is unavailable: strict (fail),
+    // use_default (`default_volatility`) or use_last_known (the symbol's last good vol)
+    pub vol_fallback: VolFallback,
+    pub update_interval_secs: u64,
+    // Oldest cached price (by publish time) that may stand in for a failed oracle fetch
+    pub max_price_age_secs: u64,
+    // Never ask the oracle for prices: price off whatever `set_cached_price` seeded, however
+    // old, and fail with `EngineError::NoCachedPrice` for anything else
+    pub offline: bool,
+    // Oracle fetches are retried with exponential backoff and jitter on connection/stream errors
+    pub fetch_max_attempts: u32,
+    pub fetch_retry_base_ms: u64,
+    // Longest a single oracle fetch attempt may take before it fails with `EngineError::Timeout`
+    pub fetch_timeout_ms: u64,
+    // Oracle fetch attempts allowed in flight at once across all symbols; the rest queue.
+    // Waiting for a turn doesn't count toward `fetch_timeout_ms`. 0 means no limit
+    pub max_concurrent_fetches: usize,
+    // Oracle prices whose confidence / price exceeds this are refused with `EngineError::LowConfidence`
+    pub max_confidence_ratio: Option<f64>,
+    // Live risk-free rate source; `risk_free_rate` is used when unset or when the source fails
+    pub rate_source: Option<HttpRateConfig>,
+    // Rate by tenor in years, `[[tenor, rate], ...]`; when set each option is discounted at the
+    // rate for its own time to expiry instead of `risk_free_rate` or `rate_source`
+    pub rate_curve: Option<RateCurve>,
+    // SQLite file every fetched price is appended to; needs the `history` feature
+    pub history_db: Option<PathBuf>,
+    // Oracle prices fetched this recently are reused instead of asking the oracle again
+    pub fetch_dedup_ms: u64,
+    // Identical premium queries within this window reuse the earlier result; 0 turns it off
+    pub premium_cache_ms: u64,
+    // Days per year when converting time to expiry: act365, act360, act36525 or trading_days
+    pub day_count: DayCount,
+    // Exercise style every quote is priced as, European or American; symbols can override it
+    pub style: OptionStyle,
+    // Options expiring sooner than this (but not yet expired) are priced as if this much time
+    // were left. Near T = 0 the model's f32 maths loses precision and gamma and theta blow up;
+    // the floor keeps them bounded at the cost of overstating time value inside it. 0 disables
+    pub min_time_to_expiry_years: f64,
+    // Listed expiry schedule; when set, a premium or curve asked for N days is priced to the
+    // listed expiry nearest N days out rather than exactly N days from now
+    pub expiry_calendar: Option<ExpiryCalendar>,
+    // How many of the latest streamed ticks per symbol background updates keep, and how
+    // pricing turns them into the underlying: last (the newest tick), mean or median
+    pub tick_window: usize,
+    pub tick_aggregation: TickAggregation,
+    // Spot Black-Scholes, or Black-76 on the forward implied by a funding rate
+    pub pricing_model: PricingModel,
+    // Also report each premium in units of the underlying (premium / spot)
+    pub quote_in_underlying: bool,
+    // How close S/K must be to 1 for a chain row to count as at the money
+    pub atm_band: f64,
+    // Most strikes a curve, chain or surface may span, counted across every expiry of a
+    // multi-expiry request; larger ones are refused before anything is priced or allocated.
+    // 0 means no limit
+    pub max_curve_points: usize,
+    // Decimals to show premiums with, by symbol; others use DEFAULT_DISPLAY_PRECISION
+    pub display_precision: HashMap<String, usize>,
+    // How Greeks are rounded in API responses (/price, /chain, /surface); results returned
+    // by the engine keep full precision. Unset serves them unrounded
+    pub greeks_rounding: Option<GreeksRounding>,
+    // Per-symbol pricing overrides, e.g. `[symbols.SUI] volatility = 1.2`
+    pub symbols: HashMap<String, SymbolConfig>,
+}
+
+/// Pricing settings for one symbol. Anything set here wins over the global setting and over
+/// what the oracle or rate source report; anything left out falls back to them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SymbolConfig {
+    // Flat vol to price at instead of the oracle's; a configured smile still takes precedence
+    pub volatility: Option<f64>,
+    pub risk_free_rate: Option<f64>,
+    pub day_count: Option<DayCount>,
+    pub style: Option<OptionStyle>,
+    // Units of the underlying per contract, e.g. 100 SUI; 1 when unset
+    pub contract_multiplier: Option<f64>,
+}
+
+/// How the ticks kept by background updates become the underlying price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TickAggregation {
+    #[default]
+    Last,
+    Mean,
+    Median,
+}
+
+impl TickAggregation {
+    /// Combines `ticks`, oldest first; None when there are none.
+    pub fn aggregate(self, ticks: &[f64]) -> Option<f64> {
+        let last = *ticks.last()?;
+        Some(match self {
+            TickAggregation::Last => last,
+            TickAggregation::Mean => ticks.iter().sum::<f64>() / ticks.len() as f64,
+            TickAggregation::Median => {
+                let mut sorted = ticks.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let middle = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) { (sorted[middle - 1] + sorted[middle]) / 2.0 } else { sorted[middle] }
+            }
+        })
+    }
+}
+
+/// What pricing does when the oracle can't provide a volatility. Every fallback is logged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolFallback {
+    /// Fail the request rather than price at a vol the oracle didn't give
+    Strict,
+    /// Price at `default_volatility`
+    #[default]
+    UseDefault,
+    /// Reuse the last vol the oracle gave for the symbol; fails if it never gave one
+    UseLastKnown,
+}
+
+/// Where a quote's volatility came from, roughly how far to trust it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolSource {
+    /// Whatever the oracle's `fetch_volatility` answered. `PythOracle` answers a fixed
+    /// placeholder per symbol rather than an estimate from market data
+    #[default]
+    #[serde(alias = "realized")]
+    Oracle,
+    /// The oracle's vol from an earlier quote, reused under `UseLastKnown` because the oracle
+    /// had none this time
+    LastKnown,
+    /// `default_volatility`, because the oracle had none
+    Default,
+    /// The caller's scenario vol or the symbol's configured one
+    Override,
+    /// The oracle's smile at the strike
+    Smile,
+}
+
+pub const DEFAULT_DISPLAY_PRECISION: usize = 2;
+
+// Pause between warm-up rounds while some symbols still have no price
+const WARM_UP_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+// Symbols `last_prices` holds a price for before evicting
+pub const MAX_CACHED_PRICES: usize = 10;
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            risk_free_rate: 0.05, // 5%
+            default_volatility: 0.8, // 80%
+            vol_fallback: VolFallback::UseDefault,
+            update_interval_secs: 10,
+            offline: false,
+            max_price_age_secs: 60,
+            fetch_max_attempts: 3,
+            fetch_retry_base_ms: 100,
+            fetch_timeout_ms: 5000,
+            max_concurrent_fetches: 8,
+            max_confidence_ratio: None,
+            rate_source: None,
+            rate_curve: None,
+            history_db: None,
+            fetch_dedup_ms: 500,
+            premium_cache_ms: 0,
+            day_count: DayCount::Act365,
+            style: OptionStyle::European,
+            min_time_to_expiry_years: 0.0,
+            expiry_calendar: None,
+            tick_window: 1,
+            tick_aggregation: TickAggregation::Last,
+            pricing_model: PricingModel::BlackScholesSpot,
+            quote_in_underlying: false,
+            atm_band: 0.02,
+            max_curve_points: 10_000,
+            // Sub-dollar underlyings need more than cents to show anything
+            display_precision: HashMap::from([("SUI".to_string(), 4)]),
+            greeks_rounding: None,
+            symbols: HashMap::new(),
+        }
+    }
+}
+
+impl EngineConfig {
+    /// Loads the engine settings from a TOML or JSON file (see [`crate::config::load`]).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        config::load(path)
+    }
+
+    /// Decimals to display `symbol`'s premiums with.
+    pub fn display_precision(&self, symbol: &str) -> usize {
+        self.display_precision.get(symbol).copied().unwrap_or(DEFAULT_DISPLAY_PRECISION)
+    }
+
+    /// Units of the underlying one `symbol` contract is written on.
+    pub fn contract_multiplier(&self, symbol: &str) -> f64 {
+        self.symbols.get(symbol).and_then(|overrides| overrides.contract_multiplier).unwrap_or(1.0)
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.fetch_max_attempts,
+            base_delay: Duration::from_millis(self.fetch_retry_base_ms),
+        }
+    }
+}
+
+// Simple Options Pricing Engine
+pub struct OptionsPricingEngine {
+    oracle: Arc<dyn PriceOracle>,
+    config: EngineConfig,
+    last_prices: Arc<RwLock<HashMap<String, PriceData>>>,
+    streamer: PriceStreamer,
+    rates: Arc<dyn RateProvider>,
+    #[cfg(feature = "history")]
+    history: Option<Arc<PriceHistory>>,
+    // Oracle fetches still running, shared by every concurrent caller for the same symbol
+    inflight: Arc<Mutex<HashMap<String, SharedFetch>>>,
+    // Turns for `max_concurrent_fetches`; None when unlimited
+    fetch_permits: Option<Arc<Semaphore>>,
+    // When each symbol was last fetched from the oracle, in milliseconds by `clock`, for
+    // `fetch_dedup_ms`
+    recent_fetches: Arc<Mutex<HashMap<String, (u64, PriceData)>>>,
+    // Recent `calculate_option_premium_at` results, for `premium_cache_ms`
+    premium_cache: Mutex<HashMap<PremiumKey, CachedPremium>>,
+    // Background tasks copying streamed ticks into `last_prices`, by symbol
+    live_feeds: Mutex<HashMap<String, JoinHandle<()>>>,
+    // The latest `tick_window` prices those tasks stored, oldest first
+    recent_ticks: Arc<Mutex<HashMap<String, VecDeque<f64>>>>,
+    shutdown: CancellationToken,
+    clock: Arc<dyn Clock>,
+    // The last vol the oracle gave for each symbol, for `VolFallback::UseLastKnown`
+    last_volatilities: Mutex<HashMap<String, f64>>,
+}
+
+// anyhow::Error isn't Clone, so concurrent awaiters share it behind an Arc
+type SharedFetch = Shared<BoxFuture<'static, Result<PriceData, Arc<anyhow::Error>>>>;
+
+impl OptionsPricingEngine {
+    pub fn new(oracle: Arc<dyn PriceOracle>, config: EngineConfig) -> Self {
+        let rates: Arc<dyn RateProvider> = match &config.rate_source {
+            Some(source) => Arc::new(HttpRateProvider::new(source.clone())),
+            None => Arc::new(ConstantRate(config.risk_free_rate)),
+        };
+        #[cfg(feature = "history")]
+        let history = config.history_db.as_ref().and_then(|path| match PriceHistory::open(path) {
+            Ok(history) => Some(Arc::new(history)),
+            Err(err) => {
+                warn!(error = %format!("{:#}", err), "Price history disabled");
+                None
+            }
+        });
+        #[cfg(not(feature = "history"))]
+        if config.history_db.is_some() {
+            warn!("history_db is set but price_engine was built without the `history` feature");
+        }
+
+        let fetch_permits = (config.max_concurrent_fetches > 0)
+            .then(|| Arc::new(Semaphore::new(config.max_concurrent_fetches)));
+
+        Self {
+            streamer: PriceStreamer::new(oracle.clone()),
+            rates,
+            oracle,
+            config,
+            last_prices: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(feature = "history")]
+            history,
+            inflight: Arc::new(Mutex::new(HashMap::new())),
+            fetch_permits,
+            recent_fetches: Arc::new(Mutex::new(HashMap::new())),
+            premium_cache: Mutex::new(HashMap::new()),
+            live_feeds: Mutex::new(HashMap::new()),
+            recent_ticks: Arc::new(Mutex::new(HashMap::new())),
+            clock: Arc::new(SystemClock),
+            last_volatilities: Mutex::new(HashMap::new()),
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Appends every fetched price to `history` instead of the `history_db` from the config.
+    #[cfg(feature = "history")]
+    pub fn with_history(mut self, history: Arc<PriceHistory>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Reads "now" from `clock` instead of the system clock, e.g. a `MockClock` in tests.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn now_secs(&self) -> u64 {
+        self.clock.now_secs()
+    }
+
+    // The oracle's own spelling of `symbol`, e.g. "SUI" for "sui/usd". Public entry points
+    // resolve once and key config overrides, caches and live feeds on the result
+    fn canonical(&self, symbol: &str) -> String {
+        self.oracle.resolve_symbol(symbol)
+    }
+
+    /// Recorded history for `symbol`, oldest first; empty when no history is configured.
+    #[cfg(feature = "history")]
+    pub fn price_history(&self, symbol: &str, limit: usize) -> Result<Vec<PriceData>> {
+        match &self.history {
+            Some(history) => history.last_prices(&self.canonical(symbol), limit),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Annualized realized vol of `symbol` over its last `limit` recorded prices.
+    #[cfg(feature = "history")]
+    pub fn realized_volatility(&self, symbol: &str, limit: usize, estimator: VolEstimator) -> Result<f64> {
+        let prices = self.price_history(symbol, limit)?;
+        estimator.estimate(&prices).with_context(|| format!("Realized volatility for {}", symbol))
+    }
+
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
+    /// Asks the oracle whether its endpoint and every configured feed answer.
+    pub async fn health_check(&self) -> Result<HealthReport> {
+        self.oracle.health_check(self.clock.as_ref()).await
+    }
+
+    /// Replaces the risk-free rate source chosen from the config.
+    pub fn with_rate_provider(mut self, rates: Arc<dyn RateProvider>) -> Self {
+        self.rates = rates;
+        self
+    }
+
+    // Live rate if the provider answers, otherwise the configured static rate
+    async fn risk_free_rate(&self) -> f64 {
+        match self.rates.risk_free_rate().await {
+            Ok(rate) => rate,
+            Err(err) => {
+                warn!(error = %format!("{:#}", err), "Using static risk-free rate");
+                self.config.risk_free_rate
+            }
+        }
+    }
+
+    /// Keeps `last_prices` current for `symbols` from one long-lived oracle stream each.
+    /// Each symbol is fetched directly once first so pricing works before the first tick.
+    ///
+    /// A symbol that fails doesn't stop the others from starting; the first failure is
+    /// returned afterwards, naming every symbol that failed. A symbol whose cold-start fetch
+    /// failed still gets its stream, so calling this again only retries unknown symbols.
+    pub async fn start_price_updates(&self, symbols: Vec<String>) -> Result<()> {
+        if self.config.offline {
+            debug!(?symbols, "Offline, not starting price updates");
+            return Ok(());
+        }
+        let mut failed = Vec::new();
+        let mut first_error = None;
+        for symbol in symbols {
+            let symbol = self.canonical(&symbol);
+            if self.live_feeds.lock().unwrap().contains_key(&symbol) {
+                continue;
+            }
+            let cold_start = self.fetch_price(&symbol).await;
+            let mut subscription = match self.subscribe(&symbol) {
+                Ok(subscription) => subscription,
+                Err(err) => {
+                    warn!(symbol, error = %format!("{:#}", err), "Not starting price updates");
+                    first_error.get_or_insert(err);
+                    failed.push(symbol);
+                    continue;
+                }
+            };
+            let tick_window = self.config.tick_window.max(1);
+            match cold_start {
+                // The cold-start price opens the tick window so smoothing works before the first tick
+                Ok(price) => store_tick(&self.recent_ticks, &symbol, price.price, tick_window),
+                Err(err) => {
+                    warn!(symbol, error = %format!("{:#}", err), "Cold-start fetch failed");
+                    first_error.get_or_insert(err);
+                    failed.push(symbol.clone());
+                }
+            }
+
+            let last_prices = self.last_prices.clone();
+            let recent_ticks = self.recent_ticks.clone();
+            let max_confidence_ratio = self.config.max_confidence_ratio;
+            let shutdown = self.shutdown.clone();
+            let feed_symbol = symbol.clone();
+            let task = tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        tick = subscription.recv() => match tick {
+                            Some(price) => match check_confidence(&price, max_confidence_ratio) {
+                                Ok(()) => {
+                                    store_tick(&recent_ticks, &feed_symbol, price.price, tick_window);
+                                    store_price(&last_prices, &feed_symbol, price);
+                                }
+                                // Keep the last confident price rather than caching this one
+                                Err(err) => warn!(symbol = feed_symbol, error = %err, "Skipping price update"),
+                            },
+                            None => break,
+                        },
+                        _ = shutdown.cancelled() => break,
+                    }
+                }
+            });
+            self.live_feeds.lock().unwrap().insert(symbol, task);
+        }
+
+        match first_error {
+            Some(err) => Err(err.context(format!("Price updates failed for {}", failed.join(", ")))),
+            None => Ok(()),
+        }
+    }
+
+    /// Fetches `symbols` until every one of them has a cached price, retrying transient
+    /// failures. Fails straight away on a non-retryable error such as an unknown symbol,
+    /// and with the symbols still missing once `timeout` runs out.
+    pub async fn warm_up(&self, symbols: &[String], timeout: Duration) -> Result<()> {
+        let missing = || -> Vec<&str> {
+            symbols.iter().map(String::as_str).filter(|symbol| self.get_last_price(symbol).is_none()).collect()
+        };
+        let fill = async {
+            loop {
+                let pending = missing();
+                if pending.is_empty() {
+                    return Ok(());
+                }
+                let results = future::join_all(pending.iter().map(|symbol| self.fetch_price(symbol))).await;
+                for (symbol, result) in pending.iter().zip(results) {
+                    match result {
+                        Err(err) if !is_retryable(&err) => return Err(err),
+                        Err(err) => debug!(symbol, error = %format!("{:#}", err), "Warm-up fetch failed, retrying"),
+                        Ok(_) => {}
+                    }
+                }
+                tokio::time::sleep(WARM_UP_RETRY_DELAY).await;
+            }
+        };
+        match tokio::time::timeout(timeout, fill).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::anyhow!("Timed out after {:?} waiting for prices: {}", timeout, missing().join(", "))),
+        }
+    }
+
+    /// Signals shutdown: background updates stop and `shutdown_requested` resolves for
+    /// anything else winding down, like the HTTP server and its WebSocket streams.
+    pub async fn shutdown(&self) {
+        self.shutdown.cancel();
+        // Let the updaters see the signal and drop their subscriptions before returning
+        let tasks: Vec<_> = self.live_feeds.lock().unwrap().drain().map(|(_, task)| task).collect();
+        for task in tasks {
+            let _ = task.await;
+        }
+    }
+
+    /// Resolves once `shutdown` has been called.
+    pub async fn shutdown_requested(&self) {
+        self.shutdown.cancelled().await
+    }
+
+    /// Stops every background update started by `start_price_updates`.
+    pub fn stop_price_updates(&self) {
+        for (_, task) in self.live_feeds.lock().unwrap().drain() {
+            task.abort();
+        }
+        self.recent_ticks.lock().unwrap().clear();
+    }
+
+    // The underlying to price at: `tick_aggregation` over the kept ticks when background
+    // updates are running for `symbol`, otherwise `price` as fetched
+    fn smoothed_underlying(&self, symbol: &str, price: f64) -> f64 {
+        if self.config.tick_aggregation == TickAggregation::Last {
+            return price;
+        }
+        let mut ticks = self.recent_ticks.lock().unwrap();
+        ticks.get_mut(symbol)
+            .and_then(|ticks| self.config.tick_aggregation.aggregate(ticks.make_contiguous()))
+            .unwrap_or(price)
+    }
+
+    /// What one unit of `currency` is worth in USD, from the oracle like any other price, for
+    /// [`PremiumResult::settled_in`]. USD itself is answered without asking the oracle.
+    pub async fn settlement(&self, currency: &str) -> Result<Settlement> {
+        if currency.eq_ignore_ascii_case("USD") {
+            return Ok(Settlement::usd());
+        }
+        let price = self.latest_price(currency).await?;
+        Settlement::new(currency, price.price)
+    }
+
+    /// Cheap read path: the cached price if it is within `max_price_age_secs`, otherwise
+    /// exactly one oracle fetch, which is cached for next time.
+    pub async fn latest_price(&self, symbol: &str) -> Result<PriceData> {
+        let symbol = &self.canonical(symbol);
+        match self.fresh_cached_price(symbol) {
+            Some(price) => Ok(price),
+            None => self.fetch_price(symbol).await,
+        }
+    }
+
+    // Pricing asks the oracle every time, unless a background stream keeps the cache live
+    async fn current_price(&self, symbol: &str) -> Result<PriceData> {
+        let live = self.live_feeds.lock().unwrap().contains_key(symbol);
+        if live {
+            self.latest_price(symbol).await
+        } else {
+            self.fetch_price(symbol).await
+        }
+    }
+
+    // Cached price for `symbol` if it was published within `max_price_age_secs`
+    fn fresh_cached_price(&self, symbol: &str) -> Option<PriceData> {
+        let cached = self.get_last_price(symbol);
+        let age = cached.as_ref().map(|price| self.now_secs().saturating_sub(price.timestamp.max(0) as u64));
+        let fresh = age.is_some_and(|age| age <= self.config.max_price_age_secs);
+
+        if cached.is_some() && !fresh {
+            metrics().stale_price_rejections.inc();
+        }
+        metrics().record_cache_lookup("price", fresh);
+        cached.filter(|_| fresh)
+    }
+
+    pub async fn calculate_option_premium(
+        &self,
+        symbol: &str,
+        strike: f64,
+        days_to_expiry: impl Into<DaysToExpiry>,
+        is_call: bool,
+    ) -> Result<PremiumResult> {
+        self.calculate_option_premium_at(symbol, strike, self.listed_expiry(days_to_expiry)?, is_call).await
+    }
+
+    /// Like `calculate_option_premium` with an explicit expiry, e.g. a UNIX timestamp for intraday options.
+    /// Options that have already expired are worth their intrinsic value.
+    #[instrument(skip(self))]
+    pub async fn calculate_option_premium_at(
+        &self,
+        symbol: &str,
+        strike: f64,
+        expiry: Expiry,
+        is_call: bool,
+    ) -> Result<PremiumResult> {
+        self.premium_at(symbol, strike, expiry, is_call, None).await
+    }
+
+    /// Scenario pricing: like `calculate_option_premium`, but at the caller's `volatility`
+    /// instead of the oracle's, the configured one or a smile's.
+    pub async fn calculate_option_premium_with_volatility(
+        &self,
+        symbol: &str,
+        strike: f64,
+        days_to_expiry: impl Into<DaysToExpiry>,
+        is_call: bool,
+        volatility: f64,
+    ) -> Result<PremiumResult> {
+        if !(volatility.is_finite() && volatility >= 0.0) {
+            return Err(invalid_param("volatility", "a non-negative number", &volatility));
+        }
+        self.premium_at(symbol, strike, self.listed_expiry(days_to_expiry)?, is_call, Some(volatility)).await
+    }
+
+    async fn premium_at(
+        &self,
+        symbol: &str,
+        strike: f64,
+        expiry: Expiry,
+        is_call: bool,
+        volatility: Option<f64>,
+    ) -> Result<PremiumResult> {
+        let symbol = &self.canonical(symbol);
+        // The contract terms can be checked before spending an oracle round trip on them;
+        // the market inputs are validated with the rest in `BlackScholes::calculate_premium`
+        if !(strike.is_finite() && strike > 0.0) {
+            return Err(invalid_param("strike_price", "a positive number", &strike));
+        }
+
+        // The cache is keyed without a volatility, so overridden quotes go around it
+        let key = premium_key(symbol, strike, expiry, is_call);
+        if volatility.is_none()
+            && let Some(result) = self.cached_premium(&key)
+        {
+            return Ok(result);
+        }
+
+        let (price_data, from_cache) = self.underlying_price(symbol).await?;
+        let underlying = self.smoothed_underlying(symbol, price_data.price);
+
+        let mut result = self
+            .premium_from_price(symbol, underlying, strike, expiry, is_call, volatility)
+            .await?;
+        result.from_cache = from_cache;
+        debug!(underlying, premium = result.premium, from_cache, "Calculated premium");
+        let option_type = if is_call { "call" } else { "put" };
+        metrics().premium_calculations.with_label_values(&[option_type]).inc();
+        if volatility.is_none() {
+            self.cache_premium(key, price_data, &result);
+        }
+        Ok(result)
+    }
+
+    /// The call and the put at one strike and expiry, as `(call, put)`, both priced off a
+    /// single fetch of the underlying.
+    #[instrument(skip(self, days_to_expiry))]
+    pub async fn calculate_straddle(
+        &self,
+        symbol: &str,
+        strike: f64,
+        days_to_expiry: impl Into<DaysToExpiry>,
+    ) -> Result<(PremiumResult, PremiumResult)> {
+        let symbol = &self.canonical(symbol);
+        if !(strike.is_finite() && strike > 0.0) {
+            return Err(invalid_param("strike_price", "a positive number", &strike));
+        }
+        let expiry = self.listed_expiry(days_to_expiry)?;
+        let (price_data, from_cache) = self.underlying_price(symbol).await?;
+        let underlying = self.smoothed_underlying(symbol, price_data.price);
+
+        let mut legs = Vec::with_capacity(2);
+        for is_call in [true, false] {
+            let mut result = self.premium_from_price(symbol, underlying, strike, expiry, is_call, None).await?;
+            result.from_cache = from_cache;
+            let option_type = if is_call { "call" } else { "put" };
+            metrics().premium_calculations.with_label_values(&[option_type]).inc();
+            self.cache_premium(premium_key(symbol, strike, expiry, is_call), price_data.clone(), &result);
+            legs.push(result);
+        }
+        let put = legs.pop().expect("priced both legs");
+        let call = legs.pop().expect("priced both legs");
+        debug!(underlying, call = call.premium, put = put.premium, from_cache, "Calculated straddle");
+        Ok((call, put))
+    }
+
+    /// What-if pricing: like `calculate_option_premium`, but against a supplied underlying price
+    /// instead of the oracle's. Volatility and rates are still looked up as usual.
+    pub async fn calculate_option_premium_with_underlying(
+        &self,
+        symbol: &str,
+        underlying_price: f64,
+        strike: f64,
+        days_to_expiry: impl Into<DaysToExpiry>,
+        is_call: bool,
+    ) -> Result<PremiumResult> {
+        if !(strike.is_finite() && strike > 0.0) {
+            return Err(invalid_param("strike_price", "a positive number", &strike));
+        }
+        self.premium_from_price(&self.canonical(symbol), underlying_price, strike, self.listed_expiry(days_to_expiry)?, is_call, None)
+            .await
+    }
+
+    // An earlier identical query's result, if it is within `premium_cache_ms` and the
+    // cached underlying hasn't moved since
+    fn cached_premium(&self, key: &PremiumKey) -> Option<PremiumResult> {
+        if self.config.premium_cache_ms == 0 {
+            return None;
+        }
+        let now = self.clock.now_millis();
+        let current = self.get_last_price(&key.0);
+        let hit = self.premium_cache.lock().unwrap().get(key)
+            .filter(|cached| now.saturating_sub(cached.at_millis) < self.config.premium_cache_ms)
+            .filter(|cached| current.as_ref().is_some_and(|price| {
+                price.timestamp == cached.underlying.timestamp && price.price == cached.underlying.price
+            }))
+            .map(|cached| cached.result.clone());
+        metrics().record_cache_lookup("premium", hit.is_some());
+        hit
+    }
+
+    fn cache_premium(&self, key: PremiumKey, underlying: PriceData, result: &PremiumResult) {
+        if self.config.premium_cache_ms == 0 {
+            return;
+        }
+        let now = self.clock.now_millis();
+        let mut cache = self.premium_cache.lock().unwrap();
+        cache.retain(|_, cached| now.saturating_sub(cached.at_millis) < self.config.premium_cache_ms);
+        cache.insert(key, CachedPremium { at_millis: now, underlying, result: result.clone() });
+    }
+
+    // `underlying_price` with its price put through `smoothed_underlying`: the one snapshot a
+    // curve, chain, surface or strategy prices off, so they quote what single premiums do
+    async fn smoothed_snapshot(&self, symbol: &str) -> Result<(PriceData, bool)> {
+        let (mut price_data, from_cache) = self.underlying_price(symbol).await?;
+        price_data.price = self.smoothed_underlying(symbol, price_data.price);
+        Ok((price_data, from_cache))
+    }
+
+    // Current oracle price, falling back to a fresh-enough cached one; the flag is true for the cache
+    async fn underlying_price(&self, symbol: &str) -> Result<(PriceData, bool)> {
+        if self.config.offline {
+            return Ok((self.offline_price(symbol)?, true));
+        }
+        match self.current_price(symbol).await {
+            Ok(price_data) => Ok((price_data, false)),
+            Err(err) => match self.fresh_cached_price(symbol) {
+                Some(cached) => Ok((cached, true)),
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Fetches the current oracle price for `symbol` and caches it.
+    ///
+    /// Concurrent calls for the same symbol share one oracle request, and a price fetched
+    /// within the last `fetch_dedup_ms` is returned without asking the oracle again.
+    pub async fn fetch_price(&self, symbol: &str) -> Result<PriceData> {
+        let symbol = &self.canonical(symbol);
+        if self.config.offline {
+            return Ok(self.offline_price(symbol)?);
+        }
+        if let Some(price_data) = self.recently_fetched(symbol) {
+            return Ok(price_data);
+        }
+
+        let fetch = self.inflight.lock().unwrap()
+            .entry(symbol.to_string())
+            .or_insert_with(|| self.start_fetch(symbol))
+            .clone();
+        let result = fetch.clone().await;
+
+        // Whoever gets here first retires the fetch so the next caller starts a fresh one
+        let mut inflight = self.inflight.lock().unwrap();
+        if inflight.get(symbol).is_some_and(|running| running.ptr_eq(&fetch)) {
+            inflight.remove(symbol);
+        }
+        drop(inflight);
+
+        result
+            .map_err(|err| shared_error(&err))
+            .context(format!("Failed to fetch price for symbol: {}", symbol))
+    }
+
+    fn offline_price(&self, symbol: &str) -> Result<PriceData, EngineError> {
+        self.get_last_price(symbol).ok_or_else(|| EngineError::NoCachedPrice(symbol.to_string()))
+    }
+
+    fn recently_fetched(&self, symbol: &str) -> Option<PriceData> {
+        let now = self.clock.now_millis();
+        let recent = self.recent_fetches.lock().unwrap();
+        recent.get(symbol)
+            .filter(|(fetched_at, _)| now.saturating_sub(*fetched_at) < self.config.fetch_dedup_ms)
+            .map(|(_, price_data)| price_data.clone())
+    }
+
+    // The oracle round trip plus its bookkeeping, run once however many callers await it
+    fn start_fetch(&self, symbol: &str) -> SharedFetch {
+        let oracle = self.oracle.clone();
+        let policy = self.config.retry_policy();
+        let fetch_timeout = Duration::from_millis(self.config.fetch_timeout_ms);
+        let max_confidence_ratio = self.config.max_confidence_ratio;
+        let last_prices = self.last_prices.clone();
+        let recent_fetches = self.recent_fetches.clone();
+        let clock = self.clock.clone();
+        let fetch_permits = self.fetch_permits.clone();
+        #[cfg(feature = "history")]
+        let history = self.history.clone();
+        let symbol = symbol.to_string();
+
+        async move {
+            let attempt = || async {
+                // Held for this attempt only, so retry backoff doesn't keep others waiting
+                let _permit = match &fetch_permits {
+                    Some(permits) => Some(permits.acquire().await.expect("fetch semaphore is never closed")),
+                    None => None,
+                };
+                match tokio::time::timeout(fetch_timeout, oracle.fetch_price(&symbol)).await {
+                    Ok(result) => {
+                        let price_data = result?;
+                        check_confidence(&price_data, max_confidence_ratio)?;
+                        Ok(price_data)
+                    }
+                    Err(_) => Err(EngineError::Timeout { symbol: symbol.clone(), after: fetch_timeout }.into()),
+                }
+            };
+            let price_data = retry(policy, attempt).await.map_err(Arc::new)?;
+            // A history write failure shouldn't fail pricing
+            #[cfg(feature = "history")]
+            if let Some(history) = &history
+                && let Err(err) = history.record(&price_data)
+            {
+                warn!(symbol, error = %format!("{:#}", err), "Failed to record price history");
+            }
+            recent_fetches.lock().unwrap().insert(symbol.clone(), (clock.now_millis(), price_data.clone()));
+            store_price(&last_prices, &symbol, price_data.clone());
+            Ok(price_data)
+        }
+        .boxed()
+        .shared()
+    }
+
+    // Prices against an already-known underlying, e.g. a tick from a live subscription
+    pub(crate) async fn premium_from_price(
+        &self,
+        symbol: &str,
+        underlying_price: f64,
+        strike: f64,
+        expiry: Expiry,
+        is_call: bool,
+        volatility: Option<f64>,
+    ) -> Result<PremiumResult> {
+        // Expired options come back at intrinsic value from `calculate_premium`
+        let days_to_expiry = expiry.days_from(self.now_secs() as i64);
+        let (params, vol_source) = self
+            .sourced_params(symbol, underlying_price, strike, days_to_expiry, is_call, volatility)
+            .await?;
+        let premium = BlackScholes::calculate_premium(&params)?;
+        // Cheap guard against unit mistakes that would otherwise quote silently
+        check_premium_bounds(&params, premium)?;
+        let prob_itm = BlackScholes::probability_itm(&params)?;
+
+        Ok(PremiumResult {
+            symbol: symbol.to_string(),
+            strike,
+            is_call,
+            premium,
+            prob_itm,
+            breakeven: breakeven(strike, premium, is_call),
+            premium_in_underlying: self.in_underlying(premium, underlying_price),
+            notional_premium: premium * self.config.contract_multiplier(symbol),
+            timestamp: self.now_secs(),
+            from_cache: false,
+            vol_source,
+            inputs: Some(params),
+        })
+    }
+
+    /// The expiry a request for `days` out is priced to: the nearest listed one when an
+    /// `expiry_calendar` is configured, exactly `days` from now otherwise. Fractional days
+    /// are intraday expiries and always exact; negative or non-finite days are refused.
+    pub fn listed_expiry(&self, days: impl Into<DaysToExpiry>) -> Result<Expiry> {
+        let days = days.into().validate()?;
+        let now = self.now_secs() as i64;
+        Ok(match (days.whole_days(), self.config.expiry_calendar) {
+            (Some(days), Some(calendar)) => Expiry::Timestamp(calendar.snap(now, days)),
+            _ => Expiry::after(days, now),
+        })
+    }
+
+    fn in_underlying(&self, premium: f64, underlying_price: f64) -> Option<f64> {
+        self.config.quote_in_underlying.then(|| premium / underlying_price)
+    }
+
+    // Pricing inputs with the oracle's volatility at `strike` and the current risk-free rate
+    // `volatility`, when given, wins over every other source and skips the oracle
+    async fn option_params(
+        &self,
+        symbol: &str,
+        underlying_price: f64,
+        strike: f64,
+        days_to_expiry: f64,
+        is_call: bool,
+        volatility: Option<f64>,
+    ) -> Result<OptionParams> {
+        let (params, _) = self
+            .sourced_params(symbol, underlying_price, strike, days_to_expiry, is_call, volatility)
+            .await?;
+        Ok(params)
+    }
+
+    // `option_params`, along with where the vol came from
+    async fn sourced_params(
+        &self,
+        symbol: &str,
+        underlying_price: f64,
+        strike: f64,
+        days_to_expiry: f64,
+        is_call: bool,
+        volatility: Option<f64>,
+    ) -> Result<(OptionParams, VolSource)> {
+        let overrides = self.config.symbols.get(symbol).cloned().unwrap_or_default();
+        let (volatility, vol_source) = match (volatility, self.oracle.volatility_smile(symbol), overrides.volatility) {
+            (Some(volatility), _, _) => (volatility, VolSource::Override),
+            (None, Some(smile), _) => (smile.vol_at(strike), VolSource::Smile),
+            (None, None, Some(volatility)) => (volatility, VolSource::Override),
+            (None, None, None) => self.oracle_volatility(symbol).await?,
+        };
+        let day_count = overrides.day_count.unwrap_or(self.config.day_count);
+        let time_to_expiry = self.floored_days(days_to_expiry, day_count);
+
+        let risk_free_rate = match (overrides.risk_free_rate, self.rate_curve(symbol)) {
+            (Some(rate), _) => rate,
+            (None, Some(curve)) => curve.rate_at(time_to_expiry.max(0.0) / day_count.days_per_year()),
+            (None, None) => self.risk_free_rate().await,
+        };
+
+        let params = OptionParams {
+            underlying_price,
+            strike_price: strike,
+            time_to_expiry,
+            volatility,
+            risk_free_rate,
+            is_call,
+            style: overrides.style.unwrap_or(self.config.style),
+            day_count,
+            model: self.config.pricing_model,
+        };
+        Ok((params, vol_source))
+    }
+
+    // `days_to_expiry` raised to `min_time_to_expiry_years`. Expired options keep their
+    // non-positive time so they still price at intrinsic
+    fn floored_days(&self, days_to_expiry: f64, day_count: DayCount) -> f64 {
+        let floor_days = self.config.min_time_to_expiry_years * day_count.days_per_year();
+        if days_to_expiry > 0.0 { days_to_expiry.max(floor_days) } else { days_to_expiry }
+    }
+
+    // Days a request listed at `expiry` is priced to, floored
+    fn priced_days(&self, expiry: Expiry, day_count: DayCount) -> f64 {
+        self.floored_days(self.days_from_now(expiry), day_count)
+    }
+
+    fn days_from_now(&self, expiry: Expiry) -> f64 {
+        expiry.days_from(self.now_secs() as i64)
+    }
+
+    // The oracle's vol for `symbol`, or whatever `vol_fallback` says to use instead
+    async fn oracle_volatility(&self, symbol: &str) -> Result<(f64, VolSource)> {
+        let err = match self.oracle.fetch_volatility(symbol).await {
+            Ok(volatility) => {
+                self.last_volatilities.lock().unwrap().insert(symbol.to_string(), volatility);
+                return Ok((volatility, VolSource::Oracle));
+            }
+            Err(err) => err,
+        };
+        match self.config.vol_fallback {
+            VolFallback::Strict => Err(err.context(format!("No volatility for {}", symbol))),
+            VolFallback::UseDefault => {
+                warn!(symbol, error = %format!("{:#}", err), "Using default volatility");
+                Ok((self.config.default_volatility, VolSource::Default))
+            }
+            VolFallback::UseLastKnown => match self.last_volatilities.lock().unwrap().get(symbol) {
+                Some(&volatility) => {
+                    warn!(symbol, volatility, error = %format!("{:#}", err), "Using last known volatility");
+                    Ok((volatility, VolSource::LastKnown))
+                }
+                None => Err(err.context(format!("No volatility for {}, and none known from before", symbol))),
+            },
+        }
+    }
+
+    // The grid for `strike_range`, or InvalidParams if the range is unusable or spans more
+    // than `max_curve_points` strikes. The count is worked out before the grid is allocated
+    fn strikes(&self, (min, max, step): (f64, f64, f64)) -> Result<Vec<f64>> {
+        if !(min > 0.0 && min <= max && step > 0.0 && max.is_finite() && step.is_finite()) {
+            return Err(EngineError::InvalidParams("strike range must satisfy 0 < min <= max and step > 0".to_string()).into());
+        }
+        // Same count as `strike_grid`, in f64 so a tiny step can't overflow it
+        let points = ((max - min) / step + 1e-9).floor() + 1.0;
+        let limit = self.config.max_curve_points;
+        if limit > 0 && points > limit as f64 {
+            return Err(EngineError::InvalidParams(format!(
+                "strike range {}..={} by {} spans {} strikes, more than max_curve_points ({})",
+                min, max, step, points, limit
+            ))
+            .into());
+        }
+        Ok(strike_grid((min, max, step)))
+    }
+
+    // InvalidParams when `expiries` rows of `strikes` strikes come to more than
+    // `max_curve_points`, which every row passing `strikes` on its own doesn't rule out
+    fn check_grid(&self, expiries: usize, strikes: usize) -> Result<()> {
+        let limit = self.config.max_curve_points;
+        let points = expiries.saturating_mul(strikes);
+        if limit > 0 && points > limit {
+            return Err(EngineError::InvalidParams(format!(
+                "{} expiries of {} strikes is {} points, more than max_curve_points ({})",
+                expiries, strikes, points, limit
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    // The configured curve, unless `symbol` pins its own flat rate
+    fn rate_curve(&self, symbol: &str) -> Option<&RateCurve> {
+        let pinned = self.config.symbols.get(symbol).is_some_and(|overrides| overrides.risk_free_rate.is_some());
+        if pinned { None } else { self.config.rate_curve.as_ref() }
+    }
+
+    pub async fn calculate_premium_curve(
+        &self,
+        symbol: &str,
+        days_to_expiry: impl Into<DaysToExpiry>,
+        strike_range: (f64, f64, f64), // (min, max, step)
+    ) -> Result<Vec<PremiumResult>> {
+        let symbol = &self.canonical(symbol);
+        let strikes = self.strikes(strike_range)?;
+        let expiry = self.listed_expiry(days_to_expiry)?;
+        // One snapshot of the inputs for the whole curve, the rest is pure CPU
+        let (price_data, from_cache) = self.smoothed_snapshot(symbol).await?;
+        self.curve_on(symbol, &price_data, from_cache, expiry, &strikes).await
+    }
+
+    /// Greeks of the call at every strike in `strike_range`, ascending, all off one underlying
+    /// snapshot. A put at the same strike has the same gamma and vega.
+    pub async fn calculate_greeks_curve(
+        &self,
+        symbol: &str,
+        days_to_expiry: impl Into<DaysToExpiry>,
+        strike_range: (f64, f64, f64), // (min, max, step)
+    ) -> Result<Vec<(f64, Greeks)>> {
+        let symbol = &self.canonical(symbol);
+        let strikes = self.strikes(strike_range)?;
+        let expiry = self.listed_expiry(days_to_expiry)?;
+        let (price_data, _) = self.smoothed_snapshot(symbol).await?;
+        let base = self
+            .option_params(symbol, price_data.price, strike_range.0, self.days_from_now(expiry), true, None)
+            .await?;
+        let smile = self.oracle.volatility_smile(symbol);
+        strikes.into_iter()
+            .map(|strike| Ok((strike, BlackScholes::calculate_greeks(&at_strike(&base, strike, smile.as_ref()))?)))
+            .collect()
+    }
+
+    /// One premium curve per distinct expiry, in ascending order of days to expiry, all priced
+    /// against a single underlying snapshot. Each curve is ordered as `calculate_premium_curve`
+    /// orders it.
+    pub async fn calculate_premium_curves(
+        &self,
+        symbol: &str,
+        expiries: &[impl Into<DaysToExpiry> + Copy],
+        strike_range: (f64, f64, f64), // (min, max, step)
+    ) -> Result<Vec<(DaysToExpiry, Vec<PremiumResult>)>> {
+        let symbol = &self.canonical(symbol);
+        if expiries.is_empty() {
+            return Err(EngineError::InvalidParams("expiries must not be empty".to_string()).into());
+        }
+        let strikes = self.strikes(strike_range)?;
+        let mut unique: Vec<DaysToExpiry> = expiries.iter().map(|&days| days.into()).collect();
+        unique.sort_unstable_by(|a, b| a.days().total_cmp(&b.days()));
+        unique.dedup();
+        self.check_grid(unique.len(), strikes.len())?;
+        let listed = unique.iter().map(|&days| self.listed_expiry(days)).collect::<Result<Vec<_>>>()?;
+        let (price_data, from_cache) = self.smoothed_snapshot(symbol).await?;
+        let mut curves = Vec::with_capacity(unique.len());
+        for (days, expiry) in unique.into_iter().zip(listed) {
+            curves.push((days, self.curve_on(symbol, &price_data, from_cache, expiry, &strikes).await?));
+        }
+        Ok(curves)
+    }
+
+    // Calls and puts alternating per strike, in ascending strike order
+    async fn curve_on(
+        &self,
+        symbol: &str,
+        price_data: &PriceData,
+        from_cache: bool,
+        expiry: Expiry,
+        strikes: &[f64],
+    ) -> Result<Vec<PremiumResult>> {
+        let (call, vol_source) = self
+            .sourced_params(symbol, price_data.price, strikes[0], self.days_from_now(expiry), true, None)
+            .await?;
+        let put = OptionParams { is_call: false, ..call.clone() };
+        let smile = self.oracle.volatility_smile(symbol);
+
+        let call_premiums = price_strikes_on(&call, strikes, smile.as_ref())?;
+        let put_premiums = price_strikes_on(&put, strikes, smile.as_ref())?;
+        metrics().premium_calculations.with_label_values(&["call"]).inc_by(strikes.len() as u64);
+        metrics().premium_calculations.with_label_values(&["put"]).inc_by(strikes.len() as u64);
+
+        let timestamp = self.now_secs();
+        let multiplier = self.config.contract_multiplier(symbol);
+        let mut results = Vec::with_capacity(strikes.len() * 2);
+        for ((&strike, call_premium), put_premium) in strikes.iter().zip(call_premiums).zip(put_premiums) {
+            let call_inputs = at_strike(&call, strike, smile.as_ref());
+            let put_inputs = at_strike(&put, strike, smile.as_ref());
+            results.push(PremiumResult {
+                symbol: symbol.to_string(),
+                strike,
+                is_call: true,
+                premium: call_premium,
+                prob_itm: BlackScholes::probability_itm(&call_inputs)?,
+                breakeven: breakeven(strike, call_premium, true),
+                premium_in_underlying: self.in_underlying(call_premium, price_data.price),
+                notional_premium: call_premium * multiplier,
+                timestamp,
+                from_cache,
+                vol_source,
+                inputs: Some(call_inputs),
+            });
+
+            results.push(PremiumResult {
+                symbol: symbol.to_string(),
+                strike,
+                is_call: false,
+                premium: put_premium,
+                prob_itm: BlackScholes::probability_itm(&put_inputs)?,
+                breakeven: breakeven(strike, put_premium, false),
+                premium_in_underlying: self.in_underlying(put_premium, price_data.price),
+                notional_premium: put_premium * multiplier,
+                timestamp,
+                from_cache,
+                vol_source,
+                inputs: Some(put_inputs),
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Calls and puts side by side, with premiums, Greeks and moneyness for every strike in
+    /// `strike_range`, all priced against one underlying snapshot.
+    pub async fn build_chain(
+        &self,
+        symbol: &str,
+        days_to_expiry: impl Into<DaysToExpiry>,
+        strike_range: (f64, f64, f64), // (min, max, step)
+    ) -> Result<OptionChain> {
+        let symbol = &self.canonical(symbol);
+        let strikes = self.strikes(strike_range)?;
+        let expiry = self.listed_expiry(days_to_expiry)?;
+        let (price_data, from_cache) = self.smoothed_snapshot(symbol).await?;
+        let base = self
+            .option_params(symbol, price_data.price, strikes[0], self.days_from_now(expiry), true, None)
+            .await?;
+        let smile = self.oracle.volatility_smile(symbol);
+
+        let mut chain = OptionChain::price(&base, &strikes, smile.as_ref(), self.config.atm_band)?;
+        chain.symbol = symbol.to_string();
+        chain.from_cache = from_cache;
+        chain.timestamp = self.now_secs();
+
+        metrics().premium_calculations.with_label_values(&["call"]).inc_by(strikes.len() as u64);
+        metrics().premium_calculations.with_label_values(&["put"]).inc_by(strikes.len() as u64);
+        Ok(chain)
+    }
+
+    /// Call and put premiums on an (expiry, strike) grid, all priced against one underlying snapshot.
+    pub async fn build_surface(
+        &self,
+        symbol: &str,
+        expiries: &[impl Into<DaysToExpiry> + Copy],
+        strike_range: (f64, f64, f64), // (min, max, step)
+    ) -> Result<Surface> {
+        self.surface(symbol, &expiries.iter().map(|&days| days.into()).collect::<Vec<_>>(), strike_range, false).await
+    }
+
+    /// Like `build_surface`, with the Greeks of every grid point filled in.
+    pub async fn build_surface_with_greeks(
+        &self,
+        symbol: &str,
+        expiries: &[impl Into<DaysToExpiry> + Copy],
+        strike_range: (f64, f64, f64),
+    ) -> Result<Surface> {
+        self.surface(symbol, &expiries.iter().map(|&days| days.into()).collect::<Vec<_>>(), strike_range, true).await
+    }
+
+    async fn surface(
+        &self,
+        symbol: &str,
+        expiries: &[DaysToExpiry],
+        strike_range: (f64, f64, f64),
+        with_greeks: bool,
+    ) -> Result<Surface> {
+        let symbol = &self.canonical(symbol);
+        if expiries.is_empty() {
+            return Err(EngineError::InvalidParams("expiries must not be empty".to_string()).into());
+        }
+        if expiries.iter().any(|days| days.days() <= 0.0) {
+            return Err(EngineError::InvalidParams("expiries must be strictly positive".to_string()).into());
+        }
+        let strikes = self.strikes(strike_range)?;
+        self.check_grid(expiries.len(), strikes.len())?;
+        let listed = expiries.iter().map(|&days| self.listed_expiry(days)).collect::<Result<Vec<_>>>()?;
+
+        let (price_data, from_cache) = self.smoothed_snapshot(symbol).await?;
+        // Struck at the money so the surface reports the ATM vol when there is a smile
+        let base = self.option_params(symbol, price_data.price, price_data.price, self.days_from_now(listed[0]), true, None).await?;
+        let smile = self.oracle.volatility_smile(symbol);
+        let times: Vec<f64> = listed.into_iter().map(|expiry| self.priced_days(expiry, base.day_count)).collect();
+
+        let mut surface = Surface::price(base, expiries, &times, &strikes, smile.as_ref(), self.rate_curve(symbol), with_greeks)?;
+        surface.symbol = symbol.to_string();
+        surface.from_cache = from_cache;
+        surface.timestamp = self.now_secs();
+
+        let points = (expiries.len() * strikes.len()) as u64;
+        metrics().premium_calculations.with_label_values(&["call"]).inc_by(points);
+        metrics().premium_calculations.with_label_values(&["put"]).inc_by(points);
+        Ok(surface)
+    }
+
+    /// Net premium of a multi-leg strategy, every leg priced against the same underlying snapshot.
+    pub async fn calculate_strategy_premium(&self, symbol: &str, strategy: &Strategy) -> Result<StrategyResult> {
+        let (price_data, from_cache, leg_params) = self.strategy_params(symbol, strategy).await?;
+
+        let legs = strategy.legs.iter().zip(&leg_params)
+            .map(|(leg, params)| {
+                let premium = BlackScholes::calculate_premium(params)?;
+                check_premium_bounds(params, premium)?;
+                Ok(LegPremium { leg: leg.clone(), premium, value: premium * leg.signed_quantity() })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut result = StrategyResult::new(price_data.price, legs);
+        result.timestamp = self.now_secs();
+        result.from_cache = from_cache;
+        Ok(result)
+    }
+
+    /// Position Greeks of a strategy: each leg's Greeks weighted by its signed quantity.
+    pub async fn calculate_strategy_greeks(&self, symbol: &str, strategy: &Strategy) -> Result<Greeks> {
+        let (_, _, leg_params) = self.strategy_params(symbol, strategy).await?;
+
+        strategy.legs.iter().zip(&leg_params)
+            .map(|(leg, params)| Ok(BlackScholes::calculate_greeks(params)?.scaled(leg.signed_quantity())))
+            .sum()
+    }
+
+    /// Net Greeks of each symbol's position, priced concurrently, with the book's total vega and
+    /// theta. Each position prices off its own underlying snapshot.
+    pub async fn calculate_portfolio_greeks(&self, portfolio: &Portfolio) -> Result<PortfolioGreeks> {
+        portfolio.validate()?;
+        // Positions entered under different spellings of one symbol net as one position
+        let mut merged: BTreeMap<String, Strategy> = BTreeMap::new();
+        for (symbol, strategy) in &portfolio.positions {
+            merged.entry(self.canonical(symbol)).or_default().legs.extend(strategy.legs.iter().cloned());
+        }
+        let positions = merged.iter().map(|(symbol, strategy)| async move {
+            let greeks = self
+                .calculate_strategy_greeks(symbol, strategy)
+                .await
+                .with_context(|| format!("Failed to price the {} position", symbol))?;
+            anyhow::Ok((symbol.clone(), greeks))
+        });
+        let by_symbol = future::try_join_all(positions).await?.into_iter().collect();
+        Ok(PortfolioGreeks::new(by_symbol))
+    }
+
+    // One underlying, vol and rate lookup shared by every leg; with a rate curve each leg is
+    // discounted at the rate for its own tenor
+    async fn strategy_params(&self, symbol: &str, strategy: &Strategy) -> Result<(PriceData, bool, Vec<OptionParams>)> {
+        let symbol = &self.canonical(symbol);
+        strategy.validate()?;
+        let listed = strategy.legs.iter().map(|leg| self.listed_expiry(leg.days)).collect::<Result<Vec<_>>>()?;
+        let (price_data, from_cache) = self.smoothed_snapshot(symbol).await?;
+        let first = &strategy.legs[0];
+        let base = self
+            .option_params(symbol, price_data.price, first.strike, self.days_from_now(listed[0]), first.is_call, None)
+            .await?;
+
+        let smile = self.oracle.volatility_smile(symbol);
+        let rates = self.rate_curve(symbol);
+        let leg_params = strategy.legs.iter().zip(listed)
+            .map(|(leg, expiry)| {
+                let time_to_expiry = self.priced_days(expiry, base.day_count);
+                OptionParams {
+                    time_to_expiry,
+                    risk_free_rate: rates.map_or(base.risk_free_rate, |curve| {
+                        curve.rate_at(time_to_expiry.max(0.0) / base.day_count.days_per_year())
+                    }),
+                    is_call: leg.is_call,
+                    ..at_strike(&base, leg.strike, smile.as_ref())
+                }
+            })
+            .collect();
+        Ok((price_data, from_cache, leg_params))
+    }
+
+    /// Symbols the oracle can price, sorted.
+    pub fn supported_symbols(&self) -> Vec<String> {
+        let mut symbols = self.oracle.symbols();
+        symbols.sort();
+        symbols
+    }
+
+    /// Every supported symbol with its oracle feed id, where the oracle has one.
+    pub fn supported_feeds(&self) -> BTreeMap<String, Option<String>> {
+        let mut feed_ids = self.oracle.feed_ids();
+        self.supported_symbols().into_iter()
+            .map(|symbol| {
+                let feed_id = feed_ids.remove(&symbol);
+                (symbol, feed_id)
+            })
+            .collect()
+    }
+
+    /// Subscribes to live ticks for `symbol`, sharing the upstream stream with other subscribers.
+    pub fn subscribe(&self, symbol: &str) -> Result<PriceSubscription> {
+        let canonical = self.canonical(symbol);
+        if !self.oracle.symbols().contains(&canonical) {
+            return Err(EngineError::UnknownSymbol(symbol.to_string()).into());
+        }
+        Ok(self.streamer.subscribe(&canonical))
+    }
+
+    /// Premiums for one option, repriced on every live tick of `symbol`'s feed rather than by
+    /// polling. The upstream stream is shared with other subscribers and released when the
+    /// returned stream is dropped.
+    pub fn premium_stream(
+        &self,
+        symbol: &str,
+        strike: f64,
+        days_to_expiry: impl Into<DaysToExpiry>,
+        is_call: bool,
+    ) -> Result<impl Stream<Item = Result<PremiumResult>> + '_> {
+        if !(strike.is_finite() && strike > 0.0) {
+            return Err(invalid_param("strike_price", "a positive number", &strike));
+        }
+        let expiry = self.listed_expiry(days_to_expiry)?;
+        let subscription = self.subscribe(symbol)?;
+        Ok(self.premiums_on(subscription, strike, expiry, is_call, None))
+    }
+
+    // One premium per tick of an existing subscription, at `volatility` when given; ends
+    // when the feed closes
+    pub(crate) fn premiums_on(
+        &self,
+        subscription: PriceSubscription,
+        strike: f64,
+        expiry: Expiry,
+        is_call: bool,
+        volatility: Option<f64>,
+    ) -> impl Stream<Item = Result<PremiumResult>> + '_ {
+        stream::unfold(subscription, move |mut subscription| async move {
+            let price = subscription.recv().await?;
+            let result = match check_confidence(&price, self.config.max_confidence_ratio) {
+                Ok(()) => self.premium_from_price(subscription.symbol(), price.price, strike, expiry, is_call, volatility).await,
+                Err(err) => Err(err.into()),
+            };
+            Some((result, subscription))
+        })
+    }
+
+    /// Caches `price` for `symbol` as if the oracle had reported it, e.g. to seed an
+    /// `offline` engine or price a what-if underlying. Unlike a fetch it leaves the fetch
+    /// dedup window, the tick window and the price history alone.
+    pub fn set_cached_price(&self, symbol: &str, price: PriceData) {
+        store_price(&self.last_prices, &self.canonical(symbol), price);
+    }
+
+    pub fn get_last_price(&self, symbol: &str) -> Option<PriceData> {
+        self.last_prices.read().unwrap().get(&self.canonical(symbol)).cloned()
+    }
+
+    /// ATM/ITM/OTM labels for `strikes` against the cached `symbol` price; see
+    /// [`classify_strikes`]. Fails when nothing is cached for `symbol` yet.
+    pub fn strike_moneyness(&self, symbol: &str, strikes: &[f64], atm_band: f64) -> Result<Vec<StrikeMoneyness>> {
+        if !(atm_band.is_finite() && atm_band >= 0.0) {
+            return Err(invalid_param("atm_band", "a non-negative number", &atm_band));
+        }
+        let price = self.get_last_price(symbol)
+            .ok_or_else(|| anyhow::anyhow!("No cached price for {}", symbol))?;
+        Ok(classify_strikes(price.price, strikes, atm_band))
+    }
+
+    /// Every cached price, copied under a single read lock so the snapshot is consistent.
+    pub fn get_all_last_prices(&self) -> HashMap<String, PriceData> {
+        self.last_prices.read().unwrap().clone()
+    }
+}
+
+// Refuses prices the oracle itself isn't sure of, when a limit is configured
+pub(crate) fn check_confidence(price: &PriceData, max_ratio: Option<f64>) -> Result<(), EngineError> {
+    match max_ratio {
+        Some(max) if price.confidence_ratio() > max => Err(EngineError::LowConfidence {
+            symbol: price.symbol.clone(),
+            ratio: price.confidence_ratio(),
+            max,
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Caches the latest price per symbol, for at most MAX_CACHED_PRICES symbols. A new symbol
+// past the limit evicts the one whose cached price was published longest ago
+fn store_price(prices: &RwLock<HashMap<String, PriceData>>, symbol: &str, price: PriceData) {
+    let mut prices = prices.write().unwrap();
+    if prices.len() >= MAX_CACHED_PRICES && !prices.contains_key(symbol) {
+        let oldest = prices.iter().min_by_key(|(_, cached)| cached.timestamp).map(|(symbol, _)| symbol.clone());
+        if let Some(oldest) = oldest {
+            prices.remove(&oldest);
+        }
+    }
+    prices.insert(symbol.to_string(), price);
+}
+
+fn store_tick(ticks: &Mutex<HashMap<String, VecDeque<f64>>>, symbol: &str, price: f64, window: usize) {
+    let mut ticks = ticks.lock().unwrap();
+    let ticks = ticks.entry(symbol.to_string()).or_default();
+    if ticks.len() == window {
+        ticks.pop_front();
+    }
+    ticks.push_back(price);
+}
+
+// Copy of a fetch error for one of its awaiters, keeping an `EngineError` downcastable
+fn shared_error(err: &anyhow::Error) -> anyhow::Error {
+    match err.downcast_ref::<EngineError>() {
+        Some(engine_err) => anyhow::Error::new(engine_err.clone()).context(format!("{:#}", err)),
+        None => anyhow::anyhow!("{:#}", err),
+    }
+}
+
+// Strikes from min to max (inclusive) in `step` increments. Each one is `min + i * step`
+// rather than a running sum, so float error doesn't build up along the grid and the last
+// strike lands on `max` when the range divides evenly.
+pub(crate) fn strike_grid((min_strike, max_strike, step): (f64, f64, f64)) -> Vec<f64> {
+    if !(step > 0.0 && step.is_finite() && min_strike.is_finite() && max_strike >= min_strike) {
+        return Vec::new();
+    }
+    // Tolerance so a range like 1.0..=2.0 by 0.1 isn't a point short when the quotient is 9.999...
+    let intervals = ((max_strike - min_strike) / step + 1e-9).floor() as usize;
+    (0..=intervals)
+        .map(|i| (min_strike + i as f64 * step).min(max_strike))
+        .collect()
+}
+
+impl Drop for OptionsPricingEngine {
+    fn drop(&mut self) {
+        self.stop_price_updates();
+    }
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn price_strikes(params: &OptionParams, strikes: &[f64]) -> Result<Vec<f64>> {
+    BlackScholes::calculate_premiums_par(params, strikes)
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn price_strikes(params: &OptionParams, strikes: &[f64]) -> Result<Vec<f64>> {
+    BlackScholes::calculate_premiums(params, strikes)
+}
+
+// (symbol, strike in 1e-8 units, expiry, is_call), so strikes that differ only by float noise share an entry
+type PremiumKey = (String, i64, Expiry, bool);
+
+struct CachedPremium {
+    // When it was cached, by the engine's clock
+    at_millis: u64,
+    underlying: PriceData,
+    result: PremiumResult,
+}
+
+fn premium_key(symbol: &str, strike: f64, expiry: Expiry, is_call: bool) -> PremiumKey {
+    (symbol.to_string(), (strike * 1e8).round() as i64, expiry, is_call)
+}
+
+// `params` struck at `strike`, on the smile's vol for that strike if there is one
+pub(crate) fn at_strike(params: &OptionParams, strike: f64, smile: Option<&VolSmile>) -> OptionParams {
+    OptionParams {
+        strike_price: strike,
+        volatility: smile.map_or(params.volatility, |smile| smile.vol_at(strike)),
+        ..params.clone()
+    }
+}
+
+// A flat vol goes through the batch pricer; a smile changes the vol per strike. Every
+// premium is bounds-checked as a single one would be, so curves, chains and surfaces fail
+// with `EngineError::PricingFailed` rather than quote an impossible price
+pub(crate) fn price_strikes_on(params: &OptionParams, strikes: &[f64], smile: Option<&VolSmile>) -> Result<Vec<f64>> {
+    let premiums = match smile {
+        None => price_strikes(params, strikes)?,
+        Some(_) => strikes.iter()
+            .map(|&strike| BlackScholes::calculate_premium(&at_strike(params, strike, smile)))
+            .collect::<Result<Vec<_>>>()?,
+    };
+    for (&strike, &premium) in strikes.iter().zip(&premiums) {
+        check_premium_bounds(&at_strike(params, strike, smile), premium)?;
+    }
+    Ok(premiums)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::time::Instant;
+    use crate::config::tests::write_temp;
+    use crate::mock_oracle::MockOracle;
+    use crate::pricing::{Moneyness, hedge_quantity};
+    use crate::strategy::Leg;
+
+    // Engine whose oracle knows SUI but fails every price fetch
+    fn offline_engine() -> OptionsPricingEngine {
+        let oracle = MockOracle::new().with_price("SUI", 2.0, 0.5);
+        oracle.fail_price("SUI", "hermes unreachable");
+        OptionsPricingEngine::new(Arc::new(oracle), EngineConfig::default())
+    }
+
+    fn cached_sui(age_secs: u64) -> PriceData {
+        PriceData {
+            symbol: "SUI".to_string(),
+            price: 2.0,
+            timestamp: (SystemClock.now_secs() - age_secs) as i64,
+            confidence: 0.001,
+            expo: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_fresh_cached_price() {
+        let engine = offline_engine();
+        engine.set_cached_price("SUI", cached_sui(5));
+
+        let result = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert!(result.from_cache);
+        assert!(result.premium > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_stale_cached_price_is_not_used() {
+        let engine = offline_engine();
+        engine.set_cached_price("SUI", cached_sui(EngineConfig::default().max_price_age_secs + 30));
+
+        assert!(engine.calculate_option_premium("SUI", 2.0, 7, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_clock_drives_staleness_and_expiry() {
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let engine = offline_engine().with_clock(clock.clone());
+        let max_age = EngineConfig::default().max_price_age_secs;
+        engine.set_cached_price("SUI", PriceData { timestamp: 1_700_000_000, ..cached_sui(0) });
+
+        // Expires exactly a day after the clock's "now"
+        let expiry = Expiry::Timestamp(1_700_000_000 + 86_400);
+        let result = engine.calculate_option_premium_at("SUI", 2.0, expiry, true).await.unwrap();
+        assert_eq!((result.timestamp, result.inputs.unwrap().time_to_expiry), (1_700_000_000, 1.0));
+
+        clock.advance(Duration::from_secs(max_age));
+        assert!(engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap().from_cache);
+        clock.advance(Duration::from_secs(1));
+        assert!(engine.calculate_option_premium("SUI", 2.0, 7, true).await.is_err());
+    }
+
+    #[test]
+    fn test_engine_config_from_toml_with_defaults() {
+        let path = write_temp("engine_partial.toml", "risk_free_rate = 0.03\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!(config.risk_free_rate, 0.03);
+        assert_eq!(config.default_volatility, EngineConfig::default().default_volatility);
+        assert_eq!(config.update_interval_secs, EngineConfig::default().update_interval_secs);
+    }
+
+    #[test]
+    fn test_engine_config_from_json() {
+        let path = write_temp("engine.json", r#"{ "default_volatility": 1.1, "update_interval_secs": 5 }"#);
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!(config.default_volatility, 1.1);
+        assert_eq!(config.update_interval_secs, 5);
+    }
+
+    #[test]
+    fn test_malformed_engine_config_names_field() {
+        let path = write_temp("engine_bad.toml", "risk_free_rate = \"high\"\n");
+        let err = EngineConfig::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("risk_free_rate"), "{}", err);
+
+        let path = write_temp("engine_bad.json", r#"{ "update_interval_secs": -1 }"#);
+        let err = EngineConfig::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("update_interval_secs"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_forward_model_from_config() {
+        let path = write_temp("engine_forward.toml", "[pricing_model.black76_forward]\nfunding_rate = 0.2\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!(config.pricing_model, PricingModel::Black76Forward { funding_rate: 0.2 });
+
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let spot = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+        let forward = OptionsPricingEngine::new(oracle, config);
+        let spot_call = spot.calculate_option_premium("SUI", 2.0, 30, true).await.unwrap();
+        let forward_call = forward.calculate_option_premium("SUI", 2.0, 30, true).await.unwrap();
+        assert!(forward_call.premium < spot_call.premium);
+    }
+
+    #[tokio::test]
+    async fn test_engine_premium_calculation() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+
+        let result = engine
+            .calculate_option_premium("SUI", 2.0, 7, true)
+            .await
+            .unwrap();
+
+        // S = K = 2.00, vol 50%, r 5%, 7/365 years
+        assert!((result.premium - 0.056174).abs() < 1e-5, "premium {}", result.premium);
+        assert_eq!(result.strike, 2.0);
+        assert_eq!(result.breakeven, 2.0 + result.premium);
+        assert!(!result.from_cache);
+    }
+
+    #[tokio::test]
+    async fn test_quote_in_underlying_and_display_precision() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let path = write_temp("engine_quote.toml", "quote_in_underlying = true\n\n[display_precision]\nBTC = 0\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!(config.display_precision("BTC"), 0);
+        assert_eq!(config.display_precision("ETH"), DEFAULT_DISPLAY_PRECISION);
+        assert_eq!(EngineConfig::default().display_precision("SUI"), 4);
+
+        let engine = OptionsPricingEngine::new(oracle, config);
+        let result = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let quote = result.premium_in_underlying.unwrap();
+        assert!((quote - result.premium / 2.0).abs() < 1e-12);
+        assert_eq!(result.formatted(4), format!("${:.4} ({:.4} in underlying)", result.premium, quote));
+
+        let curve = engine.calculate_premium_curve("SUI", 7, (2.0, 2.0, 1.0)).await.unwrap();
+        assert!(curve.iter().all(|point| point.premium_in_underlying.is_some()));
+
+        // Off by default, and left out of the JSON
+        let plain = PremiumResult { premium_in_underlying: None, ..result };
+        assert_eq!(plain.formatted(2), "$0.06");
+        assert!(serde_json::to_value(&plain).unwrap().get("premium_in_underlying").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_prices_are_refused() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig { max_confidence_ratio: Some(0.01), fetch_retry_base_ms: 1, fetch_dedup_ms: 0, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+
+        oracle.set_confidence("SUI", 0.01); // 0.5% of the price
+        engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+
+        oracle.set_confidence("SUI", 0.05); // 2.5%
+        let err = engine.fetch_price("SUI").await.unwrap_err();
+        match err.downcast_ref::<EngineError>() {
+            Some(EngineError::LowConfidence { ratio, max, .. }) => {
+                assert!((ratio - 0.025).abs() < 1e-12);
+                assert_eq!(*max, 0.01);
+            }
+            other => panic!("expected LowConfidence, got {:?}", other),
+        }
+        // The refused price never replaces the last confident one
+        assert_eq!(engine.get_last_price("SUI").unwrap().confidence, 0.01);
+    }
+
+    #[test]
+    fn test_strike_grid_has_no_drift() {
+        let strikes = strike_grid((1.0, 2.0, 0.1));
+        assert_eq!(strikes.len(), 11);
+        assert_eq!(strikes[0], 1.0);
+        assert_eq!(strikes[10], 2.0);
+        assert!((strikes[7] - 1.7).abs() < 1e-12);
+
+        // A range that doesn't divide evenly stops at the last step below max
+        assert_eq!(strike_grid((1.0, 1.25, 0.1)).len(), 3);
+        assert_eq!(strike_grid((2.0, 2.0, 1.0)), vec![2.0]);
+        assert!(strike_grid((1.0, 2.0, 0.0)).is_empty());
+        assert!(strike_grid((2.0, 1.0, 0.1)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_premium_with_underlying_skips_the_oracle_price() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        let live_call = engine.calculate_option_premium("SUI", 2.0, 30, true).await.unwrap();
+        let live_put = engine.calculate_option_premium("SUI", 2.0, 30, false).await.unwrap();
+        let fetches = oracle.price_fetches();
+
+        // SUI drops 20%
+        let call = engine.calculate_option_premium_with_underlying("SUI", 1.6, 2.0, 30, true).await.unwrap();
+        let put = engine.calculate_option_premium_with_underlying("SUI", 1.6, 2.0, 30, false).await.unwrap();
+        assert_eq!(oracle.price_fetches(), fetches);
+        assert!(call.premium < live_call.premium);
+        assert!(put.premium > live_put.premium);
+
+        assert!(engine.calculate_option_premium_with_underlying("SUI", -1.0, 2.0, 30, true).await.is_err());
+        assert!(engine.calculate_option_premium_with_underlying("SUI", 1.6, 0.0, 30, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_premium_results_survive_a_json_roundtrip() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig { quote_in_underlying: true, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle, config);
+
+        // Calls and puts at the same strikes come back as the same option types
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.1)).await.unwrap();
+        let json = serde_json::to_string(&curve).unwrap();
+        let parsed: Vec<PremiumResult> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, curve);
+        assert_eq!(parsed.iter().filter(|result| !result.is_call).count(), curve.len() / 2);
+
+        let broken = PremiumResult { premium_in_underlying: Some(f64::NAN), ..curve[0].clone() };
+        assert!(serde_json::to_string(&broken).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_premium_results_record_their_inputs() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+
+        let result = engine.calculate_option_premium("SUI", 2.1, 7, false).await.unwrap();
+        let inputs = result.inputs.clone().unwrap();
+        assert_eq!((inputs.underlying_price, inputs.strike_price, inputs.is_call), (2.0, 2.1, false));
+        assert_eq!((inputs.volatility, inputs.risk_free_rate, inputs.time_to_expiry), (0.5, 0.05, 7.0));
+
+        // A stored quote can be checked by pricing its inputs again
+        let stored: PremiumResult = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(stored.recompute().unwrap().unwrap(), result.premium);
+
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        for point in &curve {
+            assert_eq!(point.inputs.as_ref().unwrap().strike_price, point.strike);
+            assert_eq!(point.recompute().unwrap().unwrap(), point.premium);
+        }
+        assert!(PremiumResult { inputs: None, ..result }.recompute().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_premium_stream_reprices_on_each_tick() {
+        use futures::StreamExt;
+
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+        let mut premiums = Box::pin(engine.premium_stream("SUI", 2.0, 7, true).unwrap());
+
+        // The shared feed opens its upstream stream in the background
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while oracle.stream_subscribers("SUI") == 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        for price in [1.9, 2.0, 2.1] {
+            oracle.push_price("SUI", price);
+        }
+
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            let next = tokio::time::timeout(Duration::from_secs(2), premiums.next()).await.unwrap();
+            received.push(next.unwrap().unwrap().premium);
+        }
+        assert!(received[0] < received[1] && received[1] < received[2], "{:?}", received);
+        assert_eq!(engine.streamer.active_feeds(), 1);
+
+        drop(premiums);
+        assert_eq!(engine.streamer.active_feeds(), 0);
+        assert!(engine.premium_stream("DOGE", 2.0, 7, true).is_err());
+        assert!(engine.premium_stream("SUI", -2.0, 7, true).is_err());
+    }
+
+    #[test]
+    fn test_supported_symbols_are_sorted() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("BTC", 60000.0, 0.6).with_price("ETH", 3000.0, 0.7));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        assert_eq!(engine.supported_symbols(), ["BTC", "ETH", "SUI"]);
+        // The mock has no feed ids
+        assert!(engine.supported_feeds().values().all(Option::is_none));
+    }
+
+    #[tokio::test]
+    async fn test_premium_result_display_and_approx_eq() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        let call = engine.calculate_option_premium("SUI", 2.89, 7, true).await.unwrap();
+        let put = engine.calculate_option_premium("SUI", 2.89, 7, false).await.unwrap();
+
+        assert_eq!(call.to_string(), format!("SUI Call K=2.89 prem=${:.2}", call.premium));
+        assert_eq!(format!("{:.4}", put), format!("SUI Put K=2.89 prem=${:.4}", put.premium));
+
+        let nudged = PremiumResult { premium: call.premium + 1e-9, ..call.clone() };
+        assert!(call.approx_eq(&nudged, 1e-6));
+        assert!(!call.approx_eq(&nudged, 1e-12));
+        assert!(!call.approx_eq(&PremiumResult { premium: call.premium, ..put }, 1.0));
+    }
+
+    #[tokio::test]
+    async fn test_symbol_overrides_win_over_globals_and_oracle() {
+        let path = write_temp("engine_symbols.toml", r#"
+risk_free_rate = 0.05
+
+[symbols.SUI]
+volatility = 1.2
+
+[symbols.BTC]
+volatility = 0.6
+risk_free_rate = 0.01
+day_count = "act360"
+"#);
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!(config.symbols["BTC"].day_count, Some(DayCount::Act360));
+        assert_eq!(config.symbols["SUI"].risk_free_rate, None);
+
+        // The oracle says 0.5 for SUI; the override prices it at 1.2 with the global rate
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("BTC", 60000.0, 0.9));
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+        let sui = engine.option_params("SUI", 2.0, 2.0, 7.0, true, None).await.unwrap();
+        assert_eq!((sui.volatility, sui.risk_free_rate, sui.day_count), (1.2, 0.05, DayCount::Act365));
+        let btc = engine.option_params("BTC", 60000.0, 60000.0, 7.0, true, None).await.unwrap();
+        assert_eq!((btc.volatility, btc.risk_free_rate, btc.day_count), (0.6, 0.01, DayCount::Act360));
+
+        let plain = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        let overridden = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let from_oracle = plain.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert!(overridden.premium > from_oracle.premium);
+    }
+
+    #[tokio::test]
+    async fn test_symbol_aliases_share_overrides_caches_and_feeds() {
+        use futures::StreamExt;
+
+        let path = write_temp("engine_aliases.toml", "[symbols.SUI]\nvolatility = 1.2\ncontract_multiplier = 100.0\n");
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_alias("sui/usd", "SUI"));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::from_file(&path).unwrap());
+
+        let aliased = engine.calculate_option_premium("sui/usd", 2.0, 7, true).await.unwrap();
+        let canonical = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(aliased.symbol, "SUI");
+        assert_eq!(aliased.inputs.as_ref().unwrap().volatility, 1.2);
+        assert_eq!(aliased.notional_premium, aliased.premium * 100.0);
+        assert_eq!(aliased.premium, canonical.premium);
+        // Both spellings share one cached price
+        assert_eq!(engine.get_all_last_prices().keys().collect::<Vec<_>>(), ["SUI"]);
+        assert!(engine.get_last_price("sui/usd").is_some());
+
+        let mut premiums = Box::pin(engine.premium_stream("sui/usd", 2.0, 7, true).unwrap());
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while oracle.stream_subscribers("SUI") == 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        oracle.push_price("SUI", 2.1);
+        let streamed = tokio::time::timeout(Duration::from_secs(2), premiums.next()).await.unwrap().unwrap().unwrap();
+        assert_eq!((streamed.symbol.as_str(), streamed.inputs.unwrap().volatility), ("SUI", 1.2));
+
+        let err = engine.subscribe("doge/usd").err().unwrap();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::UnknownSymbol(s)) if s == "doge/usd"));
+    }
+
+    #[tokio::test]
+    async fn test_style_from_config_prices_american_options() {
+        let path = write_temp("engine_style.toml", "style = \"american\"\n\n[symbols.BTC]\nstyle = \"european\"\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!((config.style, config.symbols["BTC"].style), (OptionStyle::American, Some(OptionStyle::European)));
+
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("BTC", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, config);
+        // Deep in the money, where early exercise is worth something
+        let american = engine.calculate_option_premium("SUI", 3.0, 180, false).await.unwrap();
+        let european = engine.calculate_option_premium("BTC", 3.0, 180, false).await.unwrap();
+        assert_eq!(american.inputs.as_ref().unwrap().style, OptionStyle::American);
+        assert_eq!(european.inputs.as_ref().unwrap().style, OptionStyle::European);
+        assert!(american.premium > european.premium, "{} vs {}", american.premium, european.premium);
+        assert!(american.premium >= 1.0 - 1e-9);
+
+        let curve = engine.calculate_premium_curve("SUI", 180, (3.0, 3.0, 1.0)).await.unwrap();
+        assert!((curve[1].premium - american.premium).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_contract_multiplier_scales_notional_premium() {
+        let path = write_temp("engine_multiplier.toml", "[symbols.SUI]\ncontract_multiplier = 100.0\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!(config.contract_multiplier("SUI"), 100.0);
+        assert_eq!(config.contract_multiplier("BTC"), 1.0);
+
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("BTC", 60000.0, 0.6));
+        let engine = OptionsPricingEngine::new(oracle, config);
+        let sui = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert!((sui.notional_premium - sui.premium * 100.0).abs() < 1e-12);
+        let btc = engine.calculate_option_premium("BTC", 60000.0, 7, true).await.unwrap();
+        assert_eq!(btc.notional_premium, btc.premium);
+
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.8, 2.2, 0.2)).await.unwrap();
+        assert!(curve.iter().all(|point| (point.notional_premium - point.premium * 100.0).abs() < 1e-12));
+    }
+
+    #[tokio::test]
+    async fn test_time_to_expiry_floor_keeps_greeks_bounded() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let floor = 1.0 / (365.0 * 24.0); // one hour
+        let floored = OptionsPricingEngine::new(
+            oracle.clone(),
+            EngineConfig { min_time_to_expiry_years: floor, ..EngineConfig::default() },
+        );
+        let unfloored = OptionsPricingEngine::new(oracle, EngineConfig::default());
+
+        // Ten seconds from expiry, at the money
+        let days = 10.0 / 86_400.0;
+        let params = floored.option_params("SUI", 2.0, 2.0, days, true, None).await.unwrap();
+        assert!((params.time_in_years() - floor).abs() < 1e-15);
+        let greeks = BlackScholes::calculate_greeks(&params).unwrap();
+        assert!(greeks.gamma.is_finite() && greeks.theta.is_finite(), "{:?}", greeks);
+
+        let raw = BlackScholes::calculate_greeks(&unfloored.option_params("SUI", 2.0, 2.0, days, true, None).await.unwrap()).unwrap();
+        // Without the floor gamma is many times larger, if it's a number at all
+        assert!(raw.gamma.is_nan() || raw.gamma > greeks.gamma * 10.0, "{:?} vs {:?}", raw, greeks);
+
+        // Longer expiries and expired options are untouched
+        assert_eq!(floored.option_params("SUI", 2.0, 2.0, 7.0, true, None).await.unwrap().time_to_expiry, 7.0);
+        assert_eq!(floored.option_params("SUI", 2.0, 2.0, -1.0, true, None).await.unwrap().time_to_expiry, -1.0);
+        let expired = floored.calculate_option_premium_at("SUI", 1.5, Expiry::Timestamp(0), true).await.unwrap();
+        assert_eq!(expired.premium, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_expiry_calendar_prices_to_listed_expiry() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let path = write_temp("engine_calendar.toml", "[expiry_calendar]\nschedule = \"weekly\"\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        let calendar = config.expiry_calendar.unwrap();
+        assert_eq!((calendar.weekday, calendar.hour_utc), (5, 8));
+
+        let listed = OptionsPricingEngine::new(oracle.clone(), config);
+        let Expiry::Timestamp(expiry) = listed.listed_expiry(7).unwrap() else { panic!("expected a listed timestamp") };
+        assert_eq!(expiry, calendar.snap(SystemClock.now_secs() as i64, 7));
+
+        // Priced to exactly that expiry, which is generally not 7 days out
+        let result = listed.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let at_listed = listed.calculate_option_premium_at("SUI", 2.0, Expiry::Timestamp(expiry), true).await.unwrap();
+        assert!((result.premium - at_listed.premium).abs() < 1e-6);
+
+        let plain = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        assert_eq!(plain.listed_expiry(7).unwrap(), Expiry::Days(7));
+    }
+
+    #[tokio::test]
+    async fn test_surfaces_chains_and_legs_price_to_the_listed_expiry() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        // Monday 2024-01-01: 7 days out snaps to Friday the 5th, 4.33 days away
+        let clock = Arc::new(MockClock::new(1_704_067_200));
+        let config = EngineConfig { expiry_calendar: Some(ExpiryCalendar::default()), ..EngineConfig::default() };
+        let listed = OptionsPricingEngine::new(oracle.clone(), config.clone()).with_clock(clock.clone());
+        // A 5-day floor lifts that listed expiry, and it has to survive into every product
+        let floored = OptionsPricingEngine::new(oracle, EngineConfig { min_time_to_expiry_years: 5.0 / 365.0, ..config })
+            .with_clock(clock);
+
+        for (engine, expected_days) in [(&listed, 4.0 + 8.0 / 24.0), (&floored, 5.0)] {
+            let curve = engine.calculate_premium_curve("SUI", 7, (2.0, 2.0, 1.0)).await.unwrap();
+            let priced_days = curve[0].inputs.as_ref().unwrap().time_to_expiry;
+            assert!((priced_days - expected_days).abs() < 1e-9, "{}", priced_days);
+
+            let surface = engine.build_surface("SUI", &[7], (2.0, 2.0, 1.0)).await.unwrap();
+            assert!((surface.premium(7, 2.0, true).unwrap() - curve[0].premium).abs() < 1e-9);
+            assert_eq!(surface.times_to_expiry, [priced_days]);
+
+            let chain = engine.build_chain("SUI", 7, (2.0, 2.0, 1.0)).await.unwrap();
+            assert_eq!(chain.days_to_expiry, priced_days);
+
+            let legs = engine.calculate_strategy_premium("SUI", &Strategy::straddle(2.0, 7)).await.unwrap().legs;
+            assert!((legs[0].premium - curve[0].premium).abs() < 1e-9);
+            assert!((legs[1].premium - curve[1].premium).abs() < 1e-9);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fractional_days_are_intraday_expiries() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let path = write_temp("engine_calendar_intraday.toml", "[expiry_calendar]\nschedule = \"weekly\"\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        let now = 1_700_000_000;
+        let engine = OptionsPricingEngine::new(oracle, config).with_clock(Arc::new(MockClock::new(now)));
+
+        // A quarter day is six hours out, exactly, whatever the calendar lists
+        assert_eq!(engine.listed_expiry(0.25).unwrap(), Expiry::Timestamp(now as i64 + 6 * 3600));
+        let quarter = engine.calculate_option_premium("SUI", 2.0, 0.25, true).await.unwrap();
+        assert!((quarter.inputs.unwrap().time_to_expiry - 0.25).abs() < 1e-12);
+        let by_timestamp = engine.calculate_option_premium_at("SUI", 2.0, Expiry::Timestamp(now as i64 + 6 * 3600), true).await.unwrap();
+        assert_eq!(quarter.premium, by_timestamp.premium);
+        // Whole days, as floats or integers, still go to the listed expiry
+        assert_eq!(engine.listed_expiry(7.0).unwrap(), engine.listed_expiry(7u32).unwrap());
+
+        for days in [-1.0, f64::NAN, f64::INFINITY] {
+            let err = engine.calculate_option_premium("SUI", 2.0, days, true).await.unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(EngineError::InvalidParams(_))), "{:#}", err);
+        }
+        assert_eq!(DaysToExpiry::from(7u32).whole_days(), Some(7));
+        assert_eq!(DaysToExpiry::from(0.5).whole_days(), None);
+
+        // Curves, chains, surfaces and strategy legs take the same fractional days
+        let curve = engine.calculate_premium_curve("SUI", 0.25, (2.0, 2.0, 1.0)).await.unwrap();
+        assert_eq!(curve[0].premium, quarter.premium);
+        assert_eq!(engine.build_chain("SUI", 0.25, (2.0, 2.0, 1.0)).await.unwrap().days_to_expiry, 0.25);
+        let surface = engine.build_surface("SUI", &[0.25, 7.0], (2.0, 2.0, 1.0)).await.unwrap();
+        assert_eq!(surface.premium(0.25, 2.0, true), Some(quarter.premium));
+        let legs = engine.calculate_strategy_premium("SUI", &Strategy::straddle(2.0, 0.25)).await.unwrap().legs;
+        assert_eq!(legs[0].premium, quarter.premium);
+    }
+
+    #[tokio::test]
+    async fn test_day_count_from_config_shifts_premium() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let path = write_temp("engine_day_count.toml", "day_count = \"act36525\"\n");
+        let config = EngineConfig::from_file(&path).unwrap();
+        assert_eq!(config.day_count, DayCount::Act36525);
+
+        // 7/365.25 years
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+        let result = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert!((result.premium - 0.056154).abs() < 1e-5, "premium {}", result.premium);
+
+        let act360 = EngineConfig { day_count: DayCount::Act360, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle, act360);
+        let longer = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert!(longer.premium > result.premium);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_contract_is_rejected_before_fetching() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        for strike in [-1.0, f64::NAN] {
+            let err = engine.calculate_option_premium("SUI", strike, 7, true).await.unwrap_err();
+            assert!(matches!(err.downcast_ref::<EngineError>(), Some(EngineError::InvalidParams(_))), "{:#}", err);
+        }
+        assert_eq!(oracle.price_fetches(), 0);
+
+        // A bad volatility from the oracle is caught when the full params are validated
+        oracle.set_volatility("SUI", f64::NAN);
+        let err = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap_err();
+        assert!(err.to_string().contains("volatility"), "{:#}", err);
+    }
+
+    #[tokio::test]
+    async fn test_timestamp_expiry_prices_intraday_and_expired_options() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        let now = SystemClock.now_secs() as i64;
+
+        // Four hours out: a small but positive time value, less than a one-day option
+        let four_hours = engine
+            .calculate_option_premium_at("SUI", 2.0, Expiry::Timestamp(now + 4 * 3600), true)
+            .await
+            .unwrap();
+        let one_day = engine.calculate_option_premium("SUI", 2.0, 1, true).await.unwrap();
+        assert!(four_hours.premium > 0.0 && four_hours.premium < one_day.premium, "{:?}", four_hours);
+
+        // Already expired: just the intrinsic value
+        let expired = Expiry::Timestamp(now - 60);
+        let itm_put = engine.calculate_option_premium_at("SUI", 2.5, expired, false).await.unwrap();
+        assert!((itm_put.premium - 0.5).abs() < 1e-12);
+        let otm_call = engine.calculate_option_premium_at("SUI", 2.5, expired, true).await.unwrap();
+        assert_eq!(otm_call.premium, 0.0);
+
+        // Same for a zero-day expiry
+        let zero_day = engine.calculate_option_premium("SUI", 1.5, 0, true).await.unwrap();
+        assert!((zero_day.premium - 0.5).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetches_share_one_oracle_request() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        oracle.set_latency(Duration::from_millis(50));
+        // No dedup window, so only the in-flight sharing can collapse the calls
+        let config = EngineConfig { fetch_dedup_ms: 0, ..EngineConfig::default() };
+        let engine = Arc::new(OptionsPricingEngine::new(oracle.clone(), config));
+
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let engine = engine.clone();
+                tokio::spawn(async move { engine.calculate_option_premium("SUI", 2.0, 7, true).await })
+            })
+            .collect();
+        let premiums: Vec<f64> = futures::future::join_all(tasks).await
+            .into_iter()
+            .map(|task| task.unwrap().unwrap().premium)
+            .collect();
+        assert_eq!(oracle.price_fetches(), 1);
+        assert!(premiums.windows(2).all(|w| w[0] == w[1]));
+
+        // Once the shared fetch is over the next call asks the oracle again
+        engine.fetch_price("SUI").await.unwrap();
+        assert_eq!(oracle.price_fetches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_window_and_shared_errors() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        engine.fetch_price("SUI").await.unwrap();
+        oracle.set_price("SUI", 3.0);
+        assert_eq!(engine.fetch_price("SUI").await.unwrap().price, 2.0);
+        assert_eq!(oracle.price_fetches(), 1);
+
+        // Shared failures keep their EngineError for the server's status mapping
+        let err = engine.fetch_price("DOGE").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<EngineError>(), Some(EngineError::UnknownSymbol(_))), "{:#}", err);
+    }
+
+    #[tokio::test]
+    async fn test_background_updates_serve_streamed_prices() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig { fetch_dedup_ms: 0, ..EngineConfig::default() });
+
+        // One cold-start fetch each; the unknown symbol is reported without stopping SUI
+        let err = engine.start_price_updates(vec!["SUI".to_string(), "DOGE".to_string()]).await.unwrap_err();
+        assert!(err.to_string().contains("DOGE"), "{}", err);
+        assert_eq!(oracle.price_fetches(), 2);
+        assert_eq!(engine.get_last_price("SUI").unwrap().price, 2.0);
+
+        // Let the subscription come up, then push a tick through it
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while engine.get_last_price("SUI").unwrap().price != 2.5 {
+                oracle.push_price("SUI", 2.5);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(engine.latest_price("SUI").await.unwrap().price, 2.5);
+        engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 2);
+
+        // Without background updates every premium goes to the oracle again
+        engine.stop_price_updates();
+        engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_volatility_override() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig { premium_cache_ms: 60_000, ..EngineConfig::default() });
+        let pinned = OptionsPricingEngine::new(oracle, EngineConfig {
+            symbols: HashMap::from([("SUI".to_string(), SymbolConfig { volatility: Some(1.2), ..SymbolConfig::default() })]),
+            ..EngineConfig::default()
+        });
+
+        let oracle_vol = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let scenario = engine.calculate_option_premium_with_volatility("SUI", 2.0, 7, true, 1.2).await.unwrap();
+        let expected = pinned.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        // Not served the cached oracle-vol quote, and not cached over it either
+        assert!(scenario.approx_eq(&expected, 1e-12), "{} vs {}", scenario, expected);
+        assert!(scenario.premium > oracle_vol.premium);
+        let again = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(again.premium, oracle_vol.premium);
+
+        for bad in [-0.1, f64::NAN, f64::INFINITY] {
+            let err = engine.calculate_option_premium_with_volatility("SUI", 2.0, 7, true, bad).await.unwrap_err();
+            assert!(matches!(err.downcast_ref::<EngineError>(), Some(EngineError::InvalidParams(_))), "{:#}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_premiums_settle_through_the_oracle() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("USDC", 0.9998, 0.0));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+        let result = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let fetches = oracle.price_fetches();
+
+        let usd = engine.settlement("usd").await.unwrap();
+        assert_eq!(oracle.price_fetches(), fetches);
+        assert_eq!(result.settled_in(&usd).premium, result.premium);
+
+        let usdc = result.settled_in(&engine.settlement("USDC").await.unwrap());
+        assert!((usdc.premium - result.premium / 0.9998).abs() < 1e-15);
+        let sui = result.settled_in(&engine.settlement("SUI").await.unwrap());
+        assert!((sui.premium - result.premium / 2.0).abs() < 1e-15);
+        assert!(engine.settlement("DOGE").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_strike_moneyness_labels() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        assert!(engine.strike_moneyness("SUI", &[2.0], 0.0).is_err());
+        engine.fetch_price("SUI").await.unwrap();
+
+        let labels = engine.strike_moneyness("SUI", &[1.5, 2.0, 2.5], 0.0).unwrap();
+        let buckets: Vec<_> = labels.iter().map(|row| (row.call, row.put)).collect();
+        assert_eq!(buckets, [
+            (Moneyness::Itm, Moneyness::Otm),
+            (Moneyness::Atm, Moneyness::Atm),
+            (Moneyness::Otm, Moneyness::Itm),
+        ]);
+        assert_eq!(labels[2].moneyness, 0.8);
+
+        // The band edge counts as ATM: S/K = 2/1.9 is 1.0526..., S/K = 2/2.1 is 0.952...
+        let banded = engine.strike_moneyness("SUI", &[1.9, 2.1, 2.2], 0.053).unwrap();
+        assert_eq!(banded.iter().map(|row| row.call).collect::<Vec<_>>(), [Moneyness::Atm, Moneyness::Atm, Moneyness::Otm]);
+        assert!(engine.strike_moneyness("SUI", &[2.0], -0.1).is_err());
+
+        let json = serde_json::to_value(&labels[0]).unwrap();
+        assert_eq!((json["call"].as_str(), json["put"].as_str()), (Some("ITM"), Some("OTM")));
+    }
+
+    #[tokio::test]
+    async fn test_tick_aggregation_smooths_outliers() {
+        assert_eq!(TickAggregation::Mean.aggregate(&[1.0, 2.0, 6.0]), Some(3.0));
+        assert_eq!(TickAggregation::Median.aggregate(&[4.0, 1.0, 3.0, 2.0]), Some(2.5));
+        assert_eq!(TickAggregation::Last.aggregate(&[1.0, 2.0]), Some(2.0));
+        assert_eq!(TickAggregation::Median.aggregate(&[]), None);
+
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig {
+            tick_window: 3,
+            tick_aggregation: TickAggregation::Median,
+            fetch_dedup_ms: 0,
+            ..EngineConfig::default()
+        };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+        engine.start_price_updates(vec!["SUI".to_string()]).await.unwrap();
+
+        // Once the stream is up, push each tick exactly once; the spike pushes 2.0 out of the window
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while oracle.stream_subscribers("SUI") == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            for tick in [2.1, 2.2, 9.0] {
+                oracle.push_price("SUI", tick);
+                while engine.get_last_price("SUI").unwrap().price != tick {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        let smoothed = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let median = engine.calculate_option_premium_with_underlying("SUI", 2.2, 2.0, 7, true).await.unwrap();
+        assert!(smoothed.approx_eq(&median, 1e-12), "{} vs {}", smoothed, median);
+
+        // Once updates stop there is nothing to smooth and the oracle price is used as is
+        engine.stop_price_updates();
+        let raw = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let spike = engine.calculate_option_premium_with_underlying("SUI", 9.0, 2.0, 7, true).await.unwrap();
+        assert!(raw.approx_eq(&spike, 1e-12));
+    }
+
+    #[tokio::test]
+    async fn test_batch_quotes_are_smoothed_like_single_premiums() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig {
+            tick_window: 3,
+            tick_aggregation: TickAggregation::Median,
+            fetch_dedup_ms: 0,
+            ..EngineConfig::default()
+        };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+        engine.start_price_updates(vec!["SUI".to_string()]).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while oracle.stream_subscribers("SUI") == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            for tick in [2.1, 2.2, 9.0] {
+                oracle.push_price("SUI", tick);
+                while engine.get_last_price("SUI").unwrap().price != tick {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        // Every batch path prices off the median, 2.2, not the 9.0 spike the oracle returns
+        let curve = engine.calculate_premium_curve("SUI", 7, (2.0, 2.5, 0.5)).await.unwrap();
+        for result in &curve {
+            let single = engine.calculate_option_premium("SUI", result.strike, 7, result.is_call).await.unwrap();
+            assert!((result.premium - single.premium).abs() < 1e-12, "{} vs {}", result, single);
+        }
+        let chain = engine.build_chain("SUI", 7, (2.0, 2.5, 0.5)).await.unwrap();
+        assert_eq!((chain.underlying_price, chain.rows[0].call.premium), (2.2, curve[0].premium));
+        let surface = engine.build_surface("SUI", &[7], (2.0, 2.5, 0.5)).await.unwrap();
+        assert_eq!(surface.premium(7, 2.5, false), Some(curve[3].premium));
+        let straddle = engine.calculate_strategy_premium("SUI", &Strategy::straddle(2.0, 7)).await.unwrap();
+        assert!((straddle.legs[0].premium - curve[0].premium).abs() < 1e-12);
+        engine.stop_price_updates();
+    }
+
+    #[tokio::test]
+    async fn test_one_failing_symbol_does_not_stop_the_others() {
+        let oracle = Arc::new(
+            MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("ETH", 3000.0, 0.6).with_price("BTC", 60000.0, 0.5),
+        );
+        oracle.fail_price("ETH", "connection reset");
+        let config = EngineConfig { fetch_max_attempts: 1, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle, config);
+
+        let symbols = vec!["SUI".to_string(), "ETH".to_string(), "BTC".to_string()];
+        let err = engine.start_price_updates(symbols).await.unwrap_err();
+        assert_eq!(err.to_string(), "Price updates failed for ETH");
+        assert!(format!("{:#}", err).contains("connection reset"));
+
+        // Every symbol still got its stream; the healthy ones are priced already
+        assert_eq!(engine.live_feeds.lock().unwrap().len(), 3);
+        assert!(engine.get_last_price("SUI").is_some() && engine.get_last_price("BTC").is_some());
+        assert!(engine.get_last_price("ETH").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_waits_for_every_symbol() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("ETH", 3000.0, 0.6));
+        oracle.fail_price("ETH", "connection reset");
+        let config = EngineConfig { fetch_max_attempts: 1, fetch_dedup_ms: 0, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+        let symbols = vec!["SUI".to_string(), "ETH".to_string()];
+
+        // ETH keeps failing, so warm-up names it once time runs out
+        let err = engine.warm_up(&symbols, Duration::from_millis(250)).await.unwrap_err();
+        assert!(err.to_string().ends_with(": ETH"), "{}", err);
+        assert!(engine.get_last_price("SUI").is_some());
+
+        // Recovers mid-warm-up
+        let recovering = oracle.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            recovering.clear_failures("ETH");
+        });
+        engine.warm_up(&symbols, Duration::from_secs(2)).await.unwrap();
+        assert_eq!(engine.get_last_price("ETH").unwrap().price, 3000.0);
+
+        // Unknown symbols fail fast instead of waiting out the timeout
+        let started = Instant::now();
+        let err = engine.warm_up(&["DOGE".to_string()], Duration::from_secs(5)).await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::UnknownSymbol(_))), "{:#}", err);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_background_updates() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        engine.start_price_updates(vec!["SUI".to_string()]).await.unwrap();
+        assert_eq!(engine.live_feeds.lock().unwrap().len(), 1);
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            engine.shutdown().await;
+            engine.shutdown_requested().await;
+        })
+        .await
+        .unwrap();
+        assert!(engine.live_feeds.lock().unwrap().is_empty());
+        assert_eq!(engine.streamer.active_feeds(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_latest_price_reads_fresh_cache_without_fetching() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig { fetch_dedup_ms: 0, ..EngineConfig::default() });
+
+        // Nothing cached yet: one fetch, then served from the cache
+        assert_eq!(engine.latest_price("SUI").await.unwrap().price, 2.0);
+        oracle.set_price("SUI", 2.5);
+        assert_eq!(engine.latest_price("SUI").await.unwrap().price, 2.0);
+        assert_eq!(oracle.price_fetches(), 1);
+
+        // A stale cache entry is refreshed with exactly one fetch
+        engine.set_cached_price("SUI", cached_sui(EngineConfig::default().max_price_age_secs + 30));
+        assert_eq!(engine.latest_price("SUI").await.unwrap().price, 2.5);
+        assert_eq!(oracle.price_fetches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_last_prices_snapshot() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("BTC", 50_000.0, 0.8));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        assert!(engine.get_all_last_prices().is_empty());
+
+        engine.fetch_price("SUI").await.unwrap();
+        engine.fetch_price("BTC").await.unwrap();
+        let snapshot = engine.get_all_last_prices();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot["BTC"].price, 50_000.0);
+        assert_eq!(snapshot["SUI"].price, engine.get_last_price("SUI").unwrap().price);
+    }
+
+    #[tokio::test]
+    async fn test_premium_curve_matches_single_premiums() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.25)).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 1);
+        assert_eq!(curve.len(), 10);
+
+        // Calls and puts alternate per strike, in ascending strike order
+        for pair in curve.chunks(2) {
+            assert!(pair[0].is_call && !pair[1].is_call);
+            assert_eq!(pair[0].strike, pair[1].strike);
+            let call = engine.calculate_option_premium("SUI", pair[0].strike, 7, true).await.unwrap();
+            let put = engine.calculate_option_premium("SUI", pair[1].strike, 7, false).await.unwrap();
+            assert_eq!(pair[0].premium, call.premium);
+            assert_eq!(pair[1].premium, put.premium);
+            assert_eq!(pair[1].prob_itm, put.prob_itm);
+            assert_eq!(pair[0].breakeven, pair[0].strike + pair[0].premium);
+            assert_eq!(pair[1].breakeven, pair[1].strike - pair[1].premium);
+            assert!((pair[0].prob_itm + pair[1].prob_itm - 1.0).abs() < 1e-5);
+        }
+        assert!(curve.windows(2).all(|w| w[0].strike <= w[1].strike));
+    }
+
+    #[tokio::test]
+    async fn test_batch_paths_refuse_out_of_bounds_premiums() {
+        // A century at 100% rates discounts the strike into f32 subnormals, where the put
+        // comes out above its own ceiling
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 0.001, 2.0));
+        let config = EngineConfig { risk_free_rate: 1.0, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle, config);
+        let is_pricing_failed = |err: anyhow::Error| matches!(err.downcast_ref(), Some(EngineError::PricingFailed { kind: "put", .. }));
+
+        assert!(is_pricing_failed(engine.calculate_option_premium("SUI", 1.0, 36_500, false).await.unwrap_err()));
+        assert!(is_pricing_failed(engine.calculate_premium_curve("SUI", 36_500, (1.0, 1.0, 1.0)).await.unwrap_err()));
+        assert!(is_pricing_failed(engine.build_chain("SUI", 36_500, (1.0, 1.0, 1.0)).await.unwrap_err()));
+        assert!(is_pricing_failed(engine.build_surface("SUI", &[36_500], (1.0, 1.0, 1.0)).await.unwrap_err()));
+        let put = Strategy::new(vec![Leg::long(1.0, 36_500, false)]);
+        assert!(is_pricing_failed(engine.calculate_strategy_premium("SUI", &put).await.unwrap_err()));
+    }
+
+    #[tokio::test]
+    async fn test_max_curve_points_boundary() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig { max_curve_points: 5, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+
+        // 1.0 to 2.0 by 0.25 is exactly 5 strikes
+        assert_eq!(engine.calculate_premium_curve("SUI", 7, (1.0, 2.0, 0.25)).await.unwrap().len(), 10);
+        let invalid = |err: anyhow::Error| matches!(err.downcast_ref(), Some(EngineError::InvalidParams(_)));
+        let err = engine.calculate_premium_curve("SUI", 7, (1.0, 2.0, 0.2)).await.unwrap_err();
+        assert!(format!("{}", err).contains("6 strikes"), "{}", err);
+        assert!(invalid(err));
+        // A step that would make billions of points is refused without building them
+        assert!(invalid(engine.calculate_premium_curve("SUI", 7, (1.0, 2.0, 1e-12)).await.unwrap_err()));
+        assert!(invalid(engine.calculate_greeks_curve("SUI", 7, (1.0, 2.0, 0.2)).await.unwrap_err()));
+        assert!(invalid(engine.build_chain("SUI", 7, (1.0, 2.0, 0.2)).await.unwrap_err()));
+        assert!(invalid(engine.build_surface("SUI", &[7], (1.0, 2.0, 0.2)).await.unwrap_err()));
+        // Unusable steps are refused too rather than giving an empty curve
+        assert!(invalid(engine.calculate_premium_curve("SUI", 7, (1.0, 2.0, 0.0)).await.unwrap_err()));
+        assert!(invalid(engine.calculate_premium_curve("SUI", 7, (1.0, 2.0, f64::NAN)).await.unwrap_err()));
+        assert_eq!(oracle.price_fetches(), 1);
+
+        // Multi-expiry requests count every row: 2 x 2 strikes fits in 5, 3 x 2 doesn't
+        let two_strikes = (1.0, 2.0, 1.0);
+        assert_eq!(engine.build_surface("SUI", &[7, 14], two_strikes).await.unwrap().strikes.len(), 2);
+        assert_eq!(engine.calculate_premium_curves("SUI", &[7, 14, 14], two_strikes).await.unwrap().len(), 2);
+        let err = engine.build_surface("SUI", &[7, 14, 30], two_strikes).await.unwrap_err();
+        assert!(format!("{}", err).contains("3 expiries of 2 strikes is 6 points"), "{}", err);
+        assert!(invalid(err));
+        assert!(invalid(engine.build_surface_with_greeks("SUI", &[7, 14, 30], two_strikes).await.unwrap_err()));
+        assert!(invalid(engine.calculate_premium_curves("SUI", &[7, 14, 30], two_strikes).await.unwrap_err()));
+
+        let unlimited = OptionsPricingEngine::new(oracle, EngineConfig { max_curve_points: 0, ..EngineConfig::default() });
+        assert_eq!(unlimited.calculate_premium_curve("SUI", 7, (1.0, 2.0, 0.01)).await.unwrap().len(), 202);
+    }
+
+    #[tokio::test]
+    async fn test_straddle_fetches_once() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        let (call, put) = engine.calculate_straddle("SUI", 2.2, 30).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 1);
+        assert!(call.is_call && !put.is_call);
+        assert_eq!((call.strike, put.strike), (2.2, 2.2));
+        assert_eq!(call.premium, engine.calculate_option_premium("SUI", 2.2, 30, true).await.unwrap().premium);
+        assert_eq!(put.premium, engine.calculate_option_premium("SUI", 2.2, 30, false).await.unwrap().premium);
+        // OTM call, ITM put
+        assert!(put.premium > call.premium);
+        assert!(engine.calculate_straddle("SUI", -1.0, 30).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_chain_matches_curve() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        let chain = engine.build_chain("SUI", 7, (1.5, 2.5, 0.25)).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 1);
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.25)).await.unwrap();
+        assert_eq!(chain.rows.len(), curve.len() / 2);
+        for (row, pair) in chain.rows.iter().zip(curve.chunks(2)) {
+            assert_eq!((row.strike, row.call.premium, row.put.premium), (pair[0].strike, pair[0].premium, pair[1].premium));
+            assert!(row.call.greeks.delta > 0.0 && row.put.greeks.delta < 0.0);
+        }
+        let atm = chain.row(2.0).unwrap();
+        assert_eq!((atm.call.moneyness, atm.put.moneyness), (Moneyness::Atm, Moneyness::Atm));
+        assert_eq!(chain.row(1.5).unwrap().call.moneyness, Moneyness::Itm);
+        assert!(engine.build_chain("SUI", 7, (2.5, 1.5, 0.25)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_greeks_curve_shape() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        let curve = engine.calculate_greeks_curve("SUI", 30, (1.0, 3.0, 0.1)).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 1);
+        assert_eq!(curve.len(), 21);
+        // Call delta falls from deep ITM towards zero as the strike rises
+        assert!(curve.windows(2).all(|pair| pair[0].1.delta > pair[1].1.delta));
+        assert!(curve[0].1.delta > 0.9 && curve[20].1.delta < 0.1);
+        // Gamma peaks at the strike nearest the money
+        let peak = curve.iter().max_by(|a, b| a.1.gamma.total_cmp(&b.1.gamma)).unwrap();
+        assert!((peak.0 - 2.0).abs() <= 0.1 + 1e-9, "gamma peaks at {}", peak.0);
+    }
+
+    #[tokio::test]
+    async fn test_premium_curves_share_one_snapshot() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig { fetch_dedup_ms: 0, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+
+        let curves = engine.calculate_premium_curves("SUI", &[30, 7, 30], (1.5, 2.5, 0.5)).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 1);
+        let expiries: Vec<_> = curves.iter().map(|(days, _)| days.days()).collect();
+        assert_eq!(expiries, [7.0, 30.0]);
+        for (days, curve) in &curves {
+            let single = engine.calculate_premium_curve("SUI", *days, (1.5, 2.5, 0.5)).await.unwrap();
+            let key = |curve: &[PremiumResult]| curve.iter().map(|r| (r.strike, r.is_call, r.premium)).collect::<Vec<_>>();
+            assert_eq!(key(curve), key(&single));
+        }
+        // More time, more premium at every strike
+        assert!(curves[0].1.iter().zip(&curves[1].1).all(|(short, long)| short.premium < long.premium));
+        assert!(engine.calculate_premium_curves("SUI", &[0u32; 0], (1.5, 2.5, 0.5)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_vol_smile_lifts_otm_premiums() {
+        let flat = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let smiled = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        smiled.set_smile("SUI", VolSmile::new(vec![(1.5, 0.7), (2.0, 0.5), (2.5, 0.6)]).unwrap());
+        let flat_engine = OptionsPricingEngine::new(flat, EngineConfig::default());
+        let smile_engine = OptionsPricingEngine::new(smiled, EngineConfig::default());
+
+        // Same vol at the money, more vol (so more premium) in the wings
+        async fn premium(engine: &OptionsPricingEngine, strike: f64, is_call: bool) -> f64 {
+            engine.calculate_option_premium("SUI", strike, 30, is_call).await.unwrap().premium
+        }
+        assert_eq!(premium(&smile_engine, 2.0, true).await, premium(&flat_engine, 2.0, true).await);
+        assert!(premium(&smile_engine, 2.5, true).await > premium(&flat_engine, 2.5, true).await);
+        assert!(premium(&smile_engine, 1.5, false).await > premium(&flat_engine, 1.5, false).await);
+
+        // Curves and surfaces pick the smile up per strike too
+        let curve = smile_engine.calculate_premium_curve("SUI", 30, (1.5, 2.5, 0.5)).await.unwrap();
+        assert_eq!(curve[4].premium, premium(&smile_engine, 2.5, true).await);
+        let surface = smile_engine.build_surface("SUI", &[30], (1.5, 2.5, 0.5)).await.unwrap();
+        assert_eq!(surface.premium(30, 1.5, false), Some(premium(&smile_engine, 1.5, false).await));
+        assert_eq!(surface.volatility, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_premium_cache_reuses_identical_queries() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig { fetch_dedup_ms: 0, ..EngineConfig::default() };
+
+        // Off by default: every query prices again
+        let uncached = OptionsPricingEngine::new(oracle.clone(), config.clone());
+        uncached.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        uncached.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 2);
+
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig { premium_cache_ms: 60_000, ..config });
+        let first = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        let again = engine.calculate_option_premium("SUI", 2.0 + 1e-12, 7, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 3);
+        assert_eq!((again.premium, again.timestamp), (first.premium, first.timestamp));
+
+        // Other contract terms miss
+        engine.calculate_option_premium("SUI", 2.0, 7, false).await.unwrap();
+        engine.calculate_option_premium("SUI", 2.0, 8, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 5);
+
+        // A new underlying price invalidates the entry
+        oracle.set_price("SUI", 2.2);
+        engine.set_cached_price("SUI", PriceData { price: 2.2, ..cached_sui(0) });
+        let moved = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 6);
+        assert!(moved.premium > first.premium);
+    }
+
+    #[tokio::test]
+    async fn test_health_caches_and_dedup_run_on_the_engine_clock() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let now = SystemClock.now_secs();
+        let clock = Arc::new(MockClock::new(now + 120));
+        let config = EngineConfig { premium_cache_ms: 1_000, max_price_age_secs: 600, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config).with_clock(clock.clone());
+
+        // The oracle's price is two minutes old by the engine's clock
+        let report = engine.health_check().await.unwrap();
+        assert_eq!(report.checked_at, now as i64 + 120);
+        assert!(report.feeds[0].age_secs.unwrap() >= 120);
+        assert!(!report.is_healthy(60));
+
+        let fetches = oracle.price_fetches();
+        engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        clock.advance(Duration::from_millis(900));
+        engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), fetches + 1);
+        // Past the window by the engine's clock, however little real time went by
+        clock.advance(Duration::from_millis(200));
+        engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(oracle.price_fetches(), fetches + 2);
+
+        // That fetch is shared for `fetch_dedup_ms` (500 by default) of the engine's clock
+        clock.advance(Duration::from_millis(400));
+        engine.fetch_price("SUI").await.unwrap();
+        assert_eq!(oracle.price_fetches(), fetches + 2);
+        clock.advance(Duration::from_millis(200));
+        engine.fetch_price("SUI").await.unwrap();
+        assert_eq!(oracle.price_fetches(), fetches + 3);
+    }
+
+    #[tokio::test]
+    async fn test_surface_reuses_one_snapshot() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+
+        let surface = engine.build_surface_with_greeks("SUI", &[7, 30], (1.5, 2.5, 0.5)).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 1);
+        assert_eq!(surface.strikes, vec![1.5, 2.0, 2.5]);
+        assert_eq!(surface.calls.len(), 2);
+        assert!(surface.calls.iter().chain(&surface.puts).all(|row| row.len() == 3));
+        assert_eq!(surface.call_greeks.as_ref().unwrap()[1].len(), 3);
+
+        // Same numbers as pricing the point on its own; longer expiry is worth more
+        let single = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(surface.premium(7, 2.0, true), Some(single.premium));
+        assert!(surface.premium(30, 2.0, true) > surface.premium(7, 2.0, true));
+
+        let json = serde_json::to_value(engine.build_surface("SUI", &[7], (2.0, 2.0, 1.0)).await.unwrap()).unwrap();
+        assert!(json.get("call_greeks").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rate_curve_shifts_long_dated_premiums_more() {
+        let curve = RateCurve::new(vec![(2.0, 0.20), (0.0, 0.0)]).unwrap();
+        assert_eq!(curve.rate_at(0.0), 0.0);
+        assert!((curve.rate_at(0.5) - 0.05).abs() < 1e-12);
+        assert_eq!(curve.rate_at(5.0), 0.20);
+        assert!(serde_json::from_str::<RateCurve>("[[1.0, 0.05], [1.0, 0.06]]").is_err());
+
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let flat = OptionsPricingEngine::new(oracle.clone(), EngineConfig { risk_free_rate: 0.0, ..EngineConfig::default() });
+        let steep = OptionsPricingEngine::new(oracle, EngineConfig {
+            risk_free_rate: 0.0,
+            rate_curve: Some(curve),
+            ..EngineConfig::default()
+        });
+
+        let (short, long) = (7, 730);
+        let flat_surface = flat.build_surface("SUI", &[short, long], (2.0, 2.0, 1.0)).await.unwrap();
+        let steep_surface = steep.build_surface("SUI", &[short, long], (2.0, 2.0, 1.0)).await.unwrap();
+        let shift = |days| steep_surface.premium(days, 2.0, true).unwrap() - flat_surface.premium(days, 2.0, true).unwrap();
+        assert!(shift(short) >= 0.0);
+        assert!(shift(long) > 10.0 * shift(short), "short {} long {}", shift(short), shift(long));
+
+        // Single premiums are discounted at their own tenor too
+        let flat_long = flat.calculate_option_premium("SUI", 2.0, long, true).await.unwrap().premium;
+        let steep_long = steep.calculate_option_premium("SUI", 2.0, long, true).await.unwrap().premium;
+        assert!((steep_long - flat_long - shift(long)).abs() < 1e-4);
+
+        // So is each leg of a calendar spread, not just the first leg's tenor
+        let calendar = Strategy::new(vec![Leg::short(2.0, short, true), Leg::long(2.0, long, true)]);
+        let legs = steep.calculate_strategy_premium("SUI", &calendar).await.unwrap().legs;
+        let steep_short = steep.calculate_option_premium("SUI", 2.0, short, true).await.unwrap().premium;
+        assert!((legs[0].premium - steep_short).abs() < 1e-6, "{} vs {}", legs[0].premium, steep_short);
+        assert!((legs[1].premium - steep_long).abs() < 1e-6, "{} vs {}", legs[1].premium, steep_long);
+    }
+
+    #[tokio::test]
+    async fn test_surface_rejects_bad_expiries() {
+        let engine = OptionsPricingEngine::new(Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5)), EngineConfig::default());
+        for expiries in [&[][..], &[7, 0][..]] {
+            let err = engine.build_surface("SUI", expiries, (1.0, 2.0, 0.5)).await.unwrap_err();
+            assert!(matches!(err.downcast_ref::<EngineError>(), Some(EngineError::InvalidParams(_))), "{:#}", err);
+        }
+    }
+
+    #[cfg(feature = "history")]
+    #[tokio::test]
+    async fn test_fetched_prices_are_recorded_in_history() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig { fetch_dedup_ms: 0, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config)
+            .with_history(Arc::new(PriceHistory::in_memory().unwrap()));
+
+        engine.fetch_price("SUI").await.unwrap();
+        oracle.set_price("SUI", 2.5);
+        engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+
+        let history = engine.price_history("SUI", 10).unwrap();
+        assert_eq!(history.iter().map(|p| p.price).collect::<Vec<_>>(), vec![2.0, 2.5]);
+        // Two prices make one return, too few for a vol
+        assert!(engine.realized_volatility("SUI", 10, VolEstimator::Simple).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_strategy_premium_sums_signed_legs() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig::default());
+        let single = |strike, is_call| {
+            let engine = &engine;
+            async move { engine.calculate_option_premium("SUI", strike, 30, is_call).await.unwrap().premium }
+        };
+
+        let straddle = engine.calculate_strategy_premium("SUI", &Strategy::straddle(2.0, 30)).await.unwrap();
+        assert_eq!(oracle.price_fetches(), 1);
+        assert_eq!(straddle.legs.len(), 2);
+        assert!((straddle.net_premium - (single(2.0, true).await + single(2.0, false).await)).abs() < 1e-12);
+
+        // Bull call spread is a debit worth less than the long call alone
+        let spread = engine.calculate_strategy_premium("SUI", &Strategy::vertical_spread(2.0, 2.2, 30, true)).await.unwrap();
+        assert!(spread.net_premium > 0.0 && spread.net_premium < single(2.0, true).await);
+
+        // Iron condor opens for a credit
+        let condor = Strategy::iron_condor(1.6, 1.8, 2.2, 2.4, 30);
+        let condor = engine.calculate_strategy_premium("SUI", &condor).await.unwrap();
+        assert!(condor.net_premium < 0.0, "{:?}", condor);
+
+        // At expiry the condor keeps its credit between the short strikes and loses the wing width less it outside
+        assert!((condor.pnl_at_expiry(2.0) + condor.net_premium).abs() < 1e-12);
+        assert!((condor.pnl_at_expiry(3.0) - (-0.2 - condor.net_premium)).abs() < 1e-12);
+        assert!((straddle.pnl_at_expiry(2.0) + straddle.net_premium).abs() < 1e-12);
+
+        // Quantity and side scale each leg's value
+        let custom = Strategy::new(vec![Leg::long(1.8, 30, false).with_quantity(2.0), Leg::short(2.2, 30, true)]);
+        let custom = engine.calculate_strategy_premium("SUI", &custom).await.unwrap();
+        assert!((custom.legs[0].value - 2.0 * single(1.8, false).await).abs() < 1e-12);
+        assert!((custom.legs[1].value + single(2.2, true).await).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_strategy_greeks_net_vega() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+
+        let straddle = engine.calculate_strategy_greeks("SUI", &Strategy::straddle(2.0, 30)).await.unwrap();
+        assert!(straddle.vega > 0.0 && straddle.gamma > 0.0, "{:?}", straddle);
+
+        let condor = Strategy::iron_condor(1.6, 1.8, 2.2, 2.4, 30);
+        let condor = engine.calculate_strategy_greeks("SUI", &condor).await.unwrap();
+        assert!(condor.vega < 0.0 && condor.theta > 0.0, "{:?}", condor);
+
+        // A single long call is just that call's Greeks, scaled by quantity
+        let call = Strategy::new(vec![Leg::long(2.0, 30, true).with_quantity(3.0)]);
+        let greeks = engine.calculate_strategy_greeks("SUI", &call).await.unwrap();
+        let single = engine.calculate_strategy_greeks("SUI", &Strategy::new(vec![Leg::long(2.0, 30, true)])).await.unwrap();
+        assert!((greeks.delta - 3.0 * single.delta).abs() < 1e-12);
+
+        // A straddle at the money is close to delta-neutral already
+        assert!(hedge_quantity(&straddle, 1.0, 100.0).abs() < hedge_quantity(&single, 1.0, 100.0).abs());
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_greeks_bucket_by_symbol() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("BTC", 60000.0, 0.4));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+        let sui = Strategy::straddle(2.0, 30);
+        let portfolio = Portfolio::new()
+            .with_strategy("SUI", sui.clone())
+            .with_leg("BTC", Leg::short(60000.0, 30, true).with_quantity(0.5));
+
+        let risk = engine.calculate_portfolio_greeks(&portfolio).await.unwrap();
+        assert_eq!(risk.by_symbol.len(), 2);
+        assert_eq!(risk.by_symbol["SUI"], engine.calculate_strategy_greeks("SUI", &sui).await.unwrap());
+        // The short BTC call's delta stays with BTC, it isn't netted against SUI's
+        assert!(risk.by_symbol["BTC"].delta < -0.2, "{:?}", risk.by_symbol["BTC"]);
+        assert_eq!(risk.total_vega, risk.by_symbol["SUI"].vega + risk.by_symbol["BTC"].vega);
+        assert_eq!(risk.total_theta, risk.by_symbol["SUI"].theta + risk.by_symbol["BTC"].theta);
+
+        assert!(engine.calculate_portfolio_greeks(&Portfolio::new()).await.is_err());
+        let unknown = portfolio.with_leg("DOGE", Leg::long(0.1, 30, true));
+        let err = engine.calculate_portfolio_greeks(&unknown).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("DOGE"), "{:#}", err);
+    }
+
+    #[tokio::test]
+    async fn test_strategy_rejects_empty_and_bad_quantities() {
+        let engine = OptionsPricingEngine::new(Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5)), EngineConfig::default());
+        let bad = [Strategy::default(), Strategy::new(vec![Leg::long(2.0, 7, true).with_quantity(0.0)])];
+        for strategy in bad {
+            let err = engine.calculate_strategy_premium("SUI", &strategy).await.unwrap_err();
+            assert!(matches!(err.downcast_ref::<EngineError>(), Some(EngineError::InvalidParams(_))), "{:#}", err);
+        }
+    }
+
+    // Hermes stalling mid-stream: the connection opens but no update ever arrives
+    struct StalledOracle;
+
+    #[async_trait::async_trait]
+    impl PriceOracle for StalledOracle {
+        fn symbols(&self) -> Vec<String> {
+            vec!["SUI".to_string()]
+        }
+
+        async fn fetch_price(&self, symbol: &str) -> Result<PriceData> {
+            let mut updates = futures::stream::pending::<PriceData>();
+            futures::StreamExt::next(&mut updates).await
+                .ok_or_else(|| anyhow::anyhow!("stream for {} ended", symbol))
+        }
+
+        async fn fetch_volatility(&self, _symbol: &str) -> Result<f64> {
+            Ok(0.5)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stalled_oracle_fetch_times_out() {
+        let config = EngineConfig { fetch_timeout_ms: 20, fetch_max_attempts: 2, fetch_retry_base_ms: 1, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(Arc::new(StalledOracle), config);
+
+        let started = Instant::now();
+        let err = tokio::time::timeout(Duration::from_secs(1), engine.fetch_price("SUI"))
+            .await
+            .expect("fetch should give up on its own")
+            .unwrap_err();
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert!(
+            matches!(err.downcast_ref(), Some(EngineError::Timeout { symbol, .. }) if symbol == "SUI"),
+            "{:#}", err
+        );
+    }
+
+    // Counts fetches in flight and remembers the most seen at once
+    #[derive(Default)]
+    struct CountingOracle {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceOracle for CountingOracle {
+        fn symbols(&self) -> Vec<String> {
+            (0..8).map(|i| format!("S{}", i)).collect()
+        }
+
+        async fn fetch_price(&self, symbol: &str) -> Result<PriceData> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(PriceData { symbol: symbol.to_string(), price: 2.0, timestamp: SystemClock.now_secs() as i64, confidence: 0.0, expo: None })
+        }
+
+        async fn fetch_volatility(&self, _symbol: &str) -> Result<f64> {
+            Ok(0.5)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_offline_prices_from_seeded_cache_only() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle.clone(), EngineConfig { offline: true, ..EngineConfig::default() });
+
+        let err = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::NoCachedPrice(symbol)) if symbol == "SUI"), "{:#}", err);
+
+        // Long past `max_price_age_secs`, which offline mode doesn't apply
+        let seeded = PriceData { symbol: "SUI".to_string(), price: 2.5, timestamp: 0, confidence: 0.0, expo: None };
+        engine.set_cached_price("SUI", seeded);
+        let result = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert!(result.from_cache);
+        assert_eq!(result.inputs.unwrap().underlying_price, 2.5);
+        assert_eq!(engine.fetch_price("SUI").await.unwrap().price, 2.5);
+        engine.start_price_updates(vec!["SUI".to_string()]).await.unwrap();
+        assert_eq!((oracle.price_fetches(), oracle.stream_subscribers("SUI")), (0, 0));
+    }
+
+    #[test]
+    fn test_cached_prices_evict_the_oldest() {
+        let engine = OptionsPricingEngine::new(Arc::new(MockOracle::new()), EngineConfig::default());
+        let price = |symbol: &str, timestamp| PriceData { symbol: symbol.to_string(), price: 1.0, timestamp, confidence: 0.0, expo: None };
+        // Published in reverse order of caching, so "S9" holds the oldest price
+        for i in 0..MAX_CACHED_PRICES {
+            let symbol = format!("S{}", i);
+            engine.set_cached_price(&symbol, price(&symbol, 100 - i as i64));
+        }
+        engine.set_cached_price("S0", price("S0", 200));
+        assert_eq!(engine.get_all_last_prices().len(), MAX_CACHED_PRICES);
+
+        engine.set_cached_price("NEW", price("NEW", 300));
+        let cached = engine.get_all_last_prices();
+        assert_eq!(cached.len(), MAX_CACHED_PRICES);
+        assert!(cached.contains_key("NEW") && !cached.contains_key("S9"));
+        assert_eq!(cached["S0"].timestamp, 200);
+    }
+
+    #[tokio::test]
+    async fn test_vol_fallback_policies() {
+        let engine = |vol_fallback| {
+            let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+            let config = EngineConfig { vol_fallback, default_volatility: 0.8, ..EngineConfig::default() };
+            (oracle.clone(), OptionsPricingEngine::new(oracle, config))
+        };
+        async fn vol(engine: &OptionsPricingEngine) -> Result<f64> {
+            let result = engine.calculate_option_premium("SUI", 2.0, 7, true).await?;
+            Ok(result.inputs.unwrap().volatility)
+        }
+
+        let (oracle, strict) = engine(VolFallback::Strict);
+        assert_eq!(vol(&strict).await.unwrap(), 0.5);
+        oracle.fail_volatility("SUI", "vol source down");
+        let err = vol(&strict).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("vol source down"), "{:#}", err);
+
+        let (oracle, default) = engine(VolFallback::UseDefault);
+        oracle.fail_volatility("SUI", "vol source down");
+        assert_eq!(vol(&default).await.unwrap(), 0.8);
+
+        let (oracle, last_known) = engine(VolFallback::UseLastKnown);
+        oracle.fail_volatility("SUI", "vol source down");
+        assert!(vol(&last_known).await.is_err());
+        oracle.clear_failures("SUI");
+        oracle.set_volatility("SUI", 0.6);
+        assert_eq!(vol(&last_known).await.unwrap(), 0.6);
+        oracle.fail_volatility("SUI", "vol source down");
+        assert_eq!(vol(&last_known).await.unwrap(), 0.6);
+        // Still quoted, but not passed off as a fresh oracle vol
+        let stale = last_known.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(stale.vol_source, VolSource::LastKnown);
+        assert_eq!(serde_json::to_value(stale.vol_source).unwrap(), "last_known");
+    }
+
+    #[tokio::test]
+    async fn test_vol_source_flags_each_quote() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5).with_price("BTC", 60000.0, 0.4));
+        let mut config = EngineConfig::default();
+        config.symbols.insert("BTC".to_string(), SymbolConfig { volatility: Some(0.7), ..SymbolConfig::default() });
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+        let source = |result: PremiumResult| result.vol_source;
+
+        assert_eq!(source(engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap()), VolSource::Oracle);
+        assert_eq!(source(engine.calculate_option_premium("BTC", 60000.0, 7, true).await.unwrap()), VolSource::Override);
+        let scenario = engine.calculate_option_premium_with_volatility("SUI", 2.0, 7, true, 0.9).await.unwrap();
+        assert_eq!(source(scenario), VolSource::Override);
+
+        // No vol from the oracle: still quoted, but flagged as priced at the default
+        oracle.fail_volatility("SUI", "not enough history");
+        let guessed = engine.calculate_option_premium("SUI", 2.0, 7, true).await.unwrap();
+        assert_eq!(guessed.inputs.as_ref().unwrap().volatility, EngineConfig::default().default_volatility);
+        assert_eq!(source(guessed), VolSource::Default);
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        assert!(curve.iter().all(|result| result.vol_source == VolSource::Default));
+
+        oracle.set_smile("SUI", VolSmile::new(vec![(1.5, 0.7), (2.0, 0.5), (2.5, 0.6)]).unwrap());
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        assert!(curve.iter().all(|result| result.vol_source == VolSource::Smile));
+        let json = serde_json::to_value(&curve[0]).unwrap();
+        assert_eq!(json["vol_source"], "smile");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetches_are_capped() {
+        let oracle = Arc::new(CountingOracle::default());
+        let config = EngineConfig { max_concurrent_fetches: 3, ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle.clone(), config);
+
+        let symbols = oracle.symbols();
+        engine.warm_up(&symbols, Duration::from_secs(2)).await.unwrap();
+        assert_eq!(oracle.max_in_flight.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(engine.get_all_last_prices().len(), 8);
+    }
+
+    struct FailingRate;
+
+    #[async_trait::async_trait]
+    impl RateProvider for FailingRate {
+        async fn risk_free_rate(&self) -> Result<f64> {
+            anyhow::bail!("rate source down")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_provider_drives_premium_with_static_fallback() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let config = EngineConfig { risk_free_rate: 0.0, ..EngineConfig::default() };
+
+        let flat = OptionsPricingEngine::new(oracle.clone(), config.clone());
+        let live = OptionsPricingEngine::new(oracle.clone(), config.clone())
+            .with_rate_provider(Arc::new(ConstantRate(0.20)));
+        let down = OptionsPricingEngine::new(oracle, config)
+            .with_rate_provider(Arc::new(FailingRate));
+
+        let flat_call = flat.calculate_option_premium("SUI", 2.0, 30, true).await.unwrap().premium;
+        let live_call = live.calculate_option_premium("SUI", 2.0, 30, true).await.unwrap().premium;
+        let down_call = down.calculate_option_premium("SUI", 2.0, 30, true).await.unwrap().premium;
+
+        // Higher carry makes calls dearer; a failing source falls back to the static rate
+        assert!(live_call > flat_call);
+        assert_eq!(down_call, flat_call);
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+use thiserror::Error;
+
+// Errors callers may want to tell apart; everything else stays an anyhow context chain.
+#[derive(Debug, Clone, Error)]
+pub enum EngineError {
+    #[error("Unknown symbol: {0}")]
+    UnknownSymbol(String),
+    #[error("Invalid parameters: {0}")]
+    InvalidParams(String),
+    #[error("Oracle fetch for {symbol} timed out after {after:?}")]
+    Timeout { symbol: String, after: Duration },
+    #[error("Oracle price for {symbol} is too uncertain: confidence is {ratio:.4} of the price, above the {max} limit")]
+    LowConfidence { symbol: String, ratio: f64, max: f64 },
+    #[error("Oracle stream for {feed_id} sent {updates} updates without parsed prices")]
+    NoParsedData { feed_id: String, updates: usize },
+    #[error("Pyth update for {feed_id} failed verification: {reason}")]
+    UnverifiedUpdate { feed_id: String, reason: String },
+    #[error("No cached price for {0}, and the engine is offline")]
+    NoCachedPrice(String),
+    #[error("Pricing failed: {kind} premium {premium} is outside [0, {bound}] (underlying {underlying}, strike {strike})")]
+    PricingFailed { kind: &'static str, premium: f64, bound: f64, underlying: f64, strike: f64 },
+}
+
+impl EngineError {
+    // Whether the same request could succeed if tried again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            EngineError::UnknownSymbol(_) | EngineError::InvalidParams(_) => false,
+            // A stalled stream may well answer on a fresh connection
+            EngineError::Timeout { .. } => true,
+            // Confidence tightens again once the spike passes
+            EngineError::LowConfidence { .. } => true,
+            // Hermes not parsing for us is a setup problem a reconnect won't fix
+            EngineError::NoParsedData { .. } => false,
+            // One bad update says nothing about the next
+            EngineError::UnverifiedUpdate { .. } => true,
+            // Offline, nothing will ever fill the cache but the caller
+            EngineError::NoCachedPrice(_) => false,
+            // An impossible premium is a bug in the model, not a passing condition
+            EngineError::PricingFailed { .. } => false,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::io::Write;
+use std::sync::Arc;
+
+use crate::chain::OptionChain;
+use crate::engine::PremiumResult;
+use crate::pricing::{BlackScholes, Greeks};
+use crate::surface::Surface;
+
+// One option per row, the same columns for curves, chains and surfaces so exports can be
+// concatenated: symbol, expiry_days, strike, type ("call" or "put"), premium, then delta,
+// gamma, vega, theta and rho, which are null where the source has no Greeks. expiry_days is
+// the time each option was priced to, so with an expiry calendar or a time-to-expiry floor
+// it is the listed, floored expiry rather than the days asked for
+struct Row<'a> {
+    symbol: &'a str,
+    expiry_days: Option<f64>,
+    strike: f64,
+    is_call: bool,
+    premium: f64,
+    greeks: Option<Greeks>,
+}
+
+/// Writes a premium curve as Parquet. Greeks are worked out from each result's inputs;
+/// results without inputs get null expiries and Greeks.
+pub fn write_curve_parquet(out: impl Write + Send, curve: &[PremiumResult]) -> Result<()> {
+    let rows = curve.iter()
+        .map(|result| {
+            Ok(Row {
+                symbol: &result.symbol,
+                expiry_days: result.inputs.as_ref().map(|params| params.time_to_expiry),
+                strike: result.strike,
+                is_call: result.is_call,
+                premium: result.premium,
+                greeks: result.inputs.as_ref().map(BlackScholes::calculate_greeks).transpose()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    write_rows(out, &rows)
+}
+
+/// Writes a chain as Parquet, a call row then a put row per strike.
+pub fn write_chain_parquet(out: impl Write + Send, chain: &OptionChain) -> Result<()> {
+    let expiry_days = Some(chain.days_to_expiry);
+    let rows: Vec<Row> = chain.rows.iter()
+        .flat_map(|row| {
+            [(true, &row.call), (false, &row.put)].map(|(is_call, quote)| Row {
+                symbol: &chain.symbol,
+                expiry_days,
+                strike: row.strike,
+                is_call,
+                premium: quote.premium,
+                greeks: Some(quote.greeks),
+            })
+        })
+        .collect();
+    write_rows(out, &rows)
+}
+
+/// Writes a surface as Parquet, expiry by expiry, a call row then a put row per strike.
+/// The Greeks columns are null unless the surface was built with Greeks, and expiry_days is
+/// null for surfaces that don't record the times their rows were priced to.
+pub fn write_surface_parquet(out: impl Write + Send, surface: &Surface) -> Result<()> {
+    let mut rows = Vec::with_capacity(2 * surface.expiries.len() * surface.strikes.len());
+    for i in 0..surface.expiries.len() {
+        for (j, &strike) in surface.strikes.iter().enumerate() {
+            for is_call in [true, false] {
+                let (premiums, greeks) = if is_call {
+                    (&surface.calls, &surface.call_greeks)
+                } else {
+                    (&surface.puts, &surface.put_greeks)
+                };
+                rows.push(Row {
+                    symbol: &surface.symbol,
+                    expiry_days: surface.times_to_expiry.get(i).copied(),
+                    strike,
+                    is_call,
+                    premium: premiums[i][j],
+                    greeks: greeks.as_ref().map(|grid| grid[i][j]),
+                });
+            }
+        }
+    }
+    write_rows(out, &rows)
+}
+
+fn write_rows(out: impl Write + Send, rows: &[Row]) -> Result<()> {
+    let column = |name| Field::new(name, DataType::Float64, false);
+    let greek = |name| Field::new(name, DataType::Float64, true);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("expiry_days", DataType::Float64, true),
+        column("strike"),
+        Field::new("type", DataType::Utf8, false),
+        column("premium"),
+        greek("delta"),
+        greek("gamma"),
+        greek("vega"),
+        greek("theta"),
+        greek("rho"),
+    ]));
+
+    let floats = |value: fn(&Row) -> f64| Arc::new(Float64Array::from_iter_values(rows.iter().map(value))) as ArrayRef;
+    let greeks = |value: fn(&Greeks) -> f64| {
+        Arc::new(rows.iter().map(|row| row.greeks.as_ref().map(value)).collect::<Float64Array>()) as ArrayRef
+    };
+    let batch = RecordBatch::try_new(schema.clone(), vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.symbol))),
+        Arc::new(rows.iter().map(|row| row.expiry_days).collect::<Float64Array>()),
+        floats(|row| row.strike),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| if row.is_call { "call" } else { "put" }))),
+        floats(|row| row.premium),
+        greeks(|greeks| greeks.delta),
+        greeks(|greeks| greeks.gamma),
+        greeks(|greeks| greeks.vega),
+        greeks(|greeks| greeks.theta),
+        greeks(|greeks| greeks.rho),
+    ])?;
+
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(out, schema, Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::ExpiryCalendar;
+    use crate::clock::MockClock;
+    use crate::engine::{EngineConfig, OptionsPricingEngine};
+    use crate::mock_oracle::MockOracle;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float64Type;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn read_back(name: &str, write: impl FnOnce(std::fs::File) -> Result<()>) -> RecordBatch {
+        let path = std::env::temp_dir().join(format!("price_engine_{}_{}.parquet", name, std::process::id()));
+        write(std::fs::File::create(&path).unwrap()).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap().build().unwrap();
+        let mut batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batches.len(), 1);
+        batches.remove(0)
+    }
+
+    #[tokio::test]
+    async fn test_curve_chain_and_surface_round_trip() {
+        let oracle = std::sync::Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = OptionsPricingEngine::new(oracle, EngineConfig::default());
+
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        let batch = read_back("curve", |file| write_curve_parquet(file, &curve));
+        assert_eq!(batch.num_rows(), curve.len());
+        let column_names: Vec<_> = batch.schema().fields().iter().map(|field| field.name().clone()).collect();
+        assert_eq!(column_names, ["symbol", "expiry_days", "strike", "type", "premium", "delta", "gamma", "vega", "theta", "rho"]);
+        let premiums = batch.column_by_name("premium").unwrap().as_primitive::<Float64Type>();
+        let types = batch.column_by_name("type").unwrap().as_string::<i32>();
+        for (i, result) in curve.iter().enumerate() {
+            assert_eq!(premiums.value(i), result.premium);
+            assert_eq!(types.value(i), if result.is_call { "call" } else { "put" });
+        }
+        assert_eq!(batch.column_by_name("symbol").unwrap().as_string::<i32>().value(0), "SUI");
+        assert_eq!(batch.column_by_name("delta").unwrap().null_count(), 0);
+
+        let chain = engine.build_chain("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        let batch = read_back("chain", |file| write_chain_parquet(file, &chain));
+        assert_eq!(batch.num_rows(), 2 * chain.rows.len());
+        let deltas = batch.column_by_name("delta").unwrap().as_primitive::<Float64Type>();
+        assert_eq!(deltas.value(1), chain.rows[0].put.greeks.delta);
+
+        // Without Greeks the surface's Greeks columns are all null
+        let surface = engine.build_surface("SUI", &[7, 30], (1.5, 2.5, 0.5)).await.unwrap();
+        let batch = read_back("surface", |file| write_surface_parquet(file, &surface));
+        assert_eq!(batch.num_rows(), 2 * 2 * 3);
+        assert_eq!(batch.column_by_name("vega").unwrap().null_count(), batch.num_rows());
+        let expiries = batch.column_by_name("expiry_days").unwrap().as_primitive::<Float64Type>();
+        assert_eq!((expiries.value(0), expiries.value(11)), (7.0, 30.0));
+        let premiums = batch.column_by_name("premium").unwrap().as_primitive::<Float64Type>();
+        assert_eq!(Some(premiums.value(11)), surface.premium(30, 2.5, false));
+    }
+
+    #[tokio::test]
+    async fn test_expiry_days_is_the_priced_time_for_every_writer() {
+        let oracle = std::sync::Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        // Monday 2024-01-01: 7 days out is listed on Friday the 5th at 08:00 UTC
+        let config = EngineConfig { expiry_calendar: Some(ExpiryCalendar::default()), ..EngineConfig::default() };
+        let engine = OptionsPricingEngine::new(oracle, config).with_clock(std::sync::Arc::new(MockClock::new(1_704_067_200)));
+        let listed_days = 4.0 + 8.0 / 24.0;
+        let expiry_days = |batch: &RecordBatch| {
+            let column = batch.column_by_name("expiry_days").unwrap();
+            assert_eq!(column.null_count(), 0);
+            column.as_primitive::<Float64Type>().values().to_vec()
+        };
+
+        let curve = engine.calculate_premium_curve("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        let chain = engine.build_chain("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        let surface = engine.build_surface("SUI", &[7], (1.5, 2.5, 0.5)).await.unwrap();
+        for batch in [
+            read_back("curve_listed", |file| write_curve_parquet(file, &curve)),
+            read_back("chain_listed", |file| write_chain_parquet(file, &chain)),
+            read_back("surface_listed", |file| write_surface_parquet(file, &surface)),
+        ] {
+            for days in expiry_days(&batch) {
+                assert!((days - listed_days).abs() < 1e-9, "{}", days);
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// Serializers for f64 fields that refuse NaN and infinities. serde_json would write them as
+// `null`, which then fails to deserialize back into an f64; failing on the way out instead
+// keeps every serialized value readable again.
+use serde::Serializer;
+use serde::ser::Error;
+
+pub(crate) fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if !value.is_finite() {
+        return Err(S::Error::custom(format!("cannot serialize non-finite number {}", value)));
+    }
+    serializer.serialize_f64(*value)
+}
+
+pub(crate) mod option {
+    use serde::Serializer;
+
+    pub(crate) fn serialize<S: Serializer>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::oracle::PriceData;
+
+// Append-only log of every price fetched from the oracle, kept in SQLite
+pub struct PriceHistory {
+    conn: Mutex<Connection>,
+}
+
+impl PriceHistory {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open price history {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    /// Throwaway history, mostly for tests.
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS prices (
+                 id           INTEGER PRIMARY KEY,
+                 symbol       TEXT    NOT NULL,
+                 price        REAL    NOT NULL,
+                 confidence   REAL    NOT NULL,
+                 publish_time INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS prices_symbol_time ON prices (symbol, publish_time);",
+        )?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    pub fn record(&self, price: &PriceData) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO prices (symbol, price, confidence, publish_time) VALUES (?1, ?2, ?3, ?4)",
+            params![price.symbol, price.price, price.confidence, price.timestamp],
+        )?;
+        Ok(())
+    }
+
+    /// The `limit` most recent prices for `symbol`, oldest first.
+    pub fn last_prices(&self, symbol: &str, limit: usize) -> Result<Vec<PriceData>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT symbol, price, confidence, publish_time FROM prices
+             WHERE symbol = ?1 ORDER BY publish_time DESC, id DESC LIMIT ?2",
+        )?;
+        let mut prices = stmt
+            .query_map(params![symbol, limit as i64], |row| {
+                Ok(PriceData {
+                    symbol: row.get(0)?,
+                    price: row.get(1)?,
+                    confidence: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    expo: None,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        prices.reverse();
+        Ok(prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(symbol: &str, price: f64, timestamp: i64) -> PriceData {
+        PriceData { symbol: symbol.to_string(), price, timestamp, confidence: 0.01, expo: None }
+    }
+
+    #[test]
+    fn test_last_prices_are_per_symbol_and_chronological() {
+        let history = PriceHistory::in_memory().unwrap();
+        for (i, value) in [1.0, 1.1, 1.2, 1.3].into_iter().enumerate() {
+            history.record(&price("SUI", value, 100 + i as i64)).unwrap();
+        }
+        history.record(&price("BTC", 50_000.0, 105)).unwrap();
+
+        let last = history.last_prices("SUI", 2).unwrap();
+        assert_eq!(last.iter().map(|p| p.price).collect::<Vec<_>>(), vec![1.2, 1.3]);
+        assert_eq!(last[1].confidence, 0.01);
+        assert_eq!(history.last_prices("BTC", 10).unwrap().len(), 1);
+        assert!(history.last_prices("ETH", 10).unwrap().is_empty());
+    }
+}
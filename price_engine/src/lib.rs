@@ -0,0 +1,68 @@
+//! Off-chain options pricing engine for futsui.
+//!
+//! Streams prices from Pyth Hermes and prices call/put options with Black-Scholes.
+
+pub mod calendar;
+pub mod chain;
+pub mod clock;
+pub mod config;
+pub mod engine;
+pub mod error;
+#[cfg(feature = "parquet")]
+pub mod export;
+mod finite;
+#[cfg(feature = "history")]
+pub mod history;
+pub mod metrics;
+pub mod mock_oracle;
+pub mod oracle;
+pub mod portfolio;
+pub mod output;
+pub mod pricing;
+pub mod quote;
+pub mod rates;
+pub mod realized;
+pub mod replay_oracle;
+pub mod retry;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod smile;
+pub mod strategy;
+pub mod streamer;
+pub mod surface;
+mod verify;
+
+pub use calendar::{ExpiryCalendar, ExpirySchedule};
+pub use chain::{ChainQuote, ChainRow, OptionChain};
+pub use clock::{Clock, MockClock, SystemClock};
+pub use engine::{
+    EngineConfig, OptionsPricingEngine, PremiumResult, SymbolConfig, TickAggregation, VolFallback, VolSource,
+};
+pub use error::EngineError;
+#[cfg(feature = "history")]
+pub use history::PriceHistory;
+pub use mock_oracle::MockOracle;
+pub use oracle::{
+    FeedHealth, FeedInfo, HealthReport, OracleConfig, PriceData, PriceOracle, PriceSource, PythOracle, StreamOptions,
+    UpdateEncoding, validate_feed_id,
+};
+pub use output::OutputFormat;
+pub use portfolio::{Portfolio, PortfolioGreeks};
+pub use pricing::{
+    BinomialTree, BlackScholes, DayCount, DaysToExpiry, Expiry, Greeks, Moneyness, MonteCarlo, MonteCarloEstimate,
+    OptionParams, OptionStyle, PricedOption, PricingDetails, PricingModel, StrikeMoneyness, breakeven, check_premium_bounds,
+    classify_strikes, elasticity, hedge_quantity, payoff_curve, project_theta, simulate_expiry,
+};
+pub use quote::{
+    GreekDecimals, GreeksRounding, Quote, RoundedGreeks, RoundedQuote, SettledPremium, Settlement, SpreadParams,
+    TickRounding,
+};
+pub use rates::{ConstantRate, HttpRateConfig, HttpRateProvider, RateCurve, RateProvider};
+pub use realized::VolEstimator;
+pub use replay_oracle::ReplayOracle;
+pub use smile::VolSmile;
+pub use strategy::{Leg, LegPremium, Side, Strategy, StrategyResult};
+pub use streamer::{PriceStreamer, PriceSubscription};
+pub use surface::Surface;
@@ -0,0 +1,87 @@
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts, Registry, TextEncoder};
+use std::sync::LazyLock;
+
+// Process-wide engine metrics, rendered in the Prometheus text format on `/metrics`
+pub struct Metrics {
+    registry: Registry,
+    pub oracle_fetch_seconds: Histogram,
+    pub oracle_fetch_failures: IntCounter,
+    pub premium_calculations: IntCounterVec,
+    pub stale_price_rejections: IntCounter,
+    pub cache_lookups: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("price_engine".to_string()), None).unwrap();
+
+        let oracle_fetch_seconds = Histogram::with_opts(
+            HistogramOpts::new("oracle_fetch_seconds", "Latency of a single oracle price fetch")
+                .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+        ).unwrap();
+        let oracle_fetch_failures = IntCounter::new(
+            "oracle_fetch_failures_total", "Oracle price fetches that returned an error",
+        ).unwrap();
+        let premium_calculations = IntCounterVec::new(
+            Opts::new("premium_calculations_total", "Option premiums calculated"),
+            &["type"],
+        ).unwrap();
+        let stale_price_rejections = IntCounter::new(
+            "stale_price_rejections_total", "Cached prices rejected for being older than max_price_age_secs",
+        ).unwrap();
+        let cache_lookups = IntCounterVec::new(
+            Opts::new("cache_lookups_total", "Cache lookups by cache and outcome"),
+            &["cache", "result"],
+        ).unwrap();
+
+        registry.register(Box::new(oracle_fetch_seconds.clone())).unwrap();
+        registry.register(Box::new(oracle_fetch_failures.clone())).unwrap();
+        registry.register(Box::new(premium_calculations.clone())).unwrap();
+        registry.register(Box::new(stale_price_rejections.clone())).unwrap();
+        registry.register(Box::new(cache_lookups.clone())).unwrap();
+
+        Self {
+            registry,
+            oracle_fetch_seconds,
+            oracle_fetch_failures,
+            premium_calculations,
+            stale_price_rejections,
+            cache_lookups,
+        }
+    }
+
+    pub fn record_cache_lookup(&self, cache: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.cache_lookups.with_label_values(&[cache, result]).inc();
+    }
+
+    /// All metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding into a Vec cannot fail");
+        String::from_utf8(buffer).expect("prometheus text format is UTF-8")
+    }
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_includes_recorded_metrics() {
+        metrics().premium_calculations.with_label_values(&["call"]).inc();
+        metrics().record_cache_lookup("price", true);
+
+        let text = metrics().render();
+        assert!(text.contains("price_engine_premium_calculations_total{type=\"call\"}"), "{}", text);
+        assert!(text.contains("price_engine_cache_lookups_total{cache=\"price\",result=\"hit\"}"), "{}", text);
+    }
+}
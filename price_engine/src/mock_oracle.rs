@@ -0,0 +1,204 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::error::EngineError;
+use crate::oracle::{PriceData, PriceOracle};
+use crate::smile::VolSmile;
+
+#[derive(Debug, Clone, Default)]
+struct MockFeed {
+    price: f64,
+    confidence: f64,
+    volatility: f64,
+    smile: Option<VolSmile>,
+    price_error: Option<String>,
+    volatility_error: Option<String>,
+}
+
+/// In-memory oracle for tests: serves configured prices and vols and can be told to fail.
+#[derive(Debug, Default)]
+pub struct MockOracle {
+    feeds: Mutex<HashMap<String, MockFeed>>,
+    price_fetches: AtomicUsize,
+    latency: Mutex<Duration>,
+    ticks: Mutex<HashMap<String, broadcast::Sender<PriceData>>>,
+    // Other spellings `resolve_symbol` maps onto a configured symbol
+    aliases: HashMap<String, String>,
+}
+
+impl MockOracle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_price(self, symbol: &str, price: f64, volatility: f64) -> Self {
+        self.set_price(symbol, price);
+        self.set_volatility(symbol, volatility);
+        self
+    }
+
+    /// Makes `resolve_symbol` answer `symbol` for `alias`, like the Pyth oracle's aliases.
+    pub fn with_alias(mut self, alias: &str, symbol: &str) -> Self {
+        self.aliases.insert(alias.to_string(), symbol.to_string());
+        self
+    }
+
+    pub fn set_price(&self, symbol: &str, price: f64) {
+        self.feeds.lock().unwrap().entry(symbol.to_string()).or_default().price = price;
+    }
+
+    /// Sets the price and pushes it as a tick to every open `stream_prices` stream for `symbol`.
+    pub fn push_price(&self, symbol: &str, price: f64) {
+        self.set_price(symbol, price);
+        let _ = self.tick_sender(symbol).send(self.price_data(symbol, price));
+    }
+
+    /// Confidence interval reported with every later price for `symbol`.
+    pub fn set_confidence(&self, symbol: &str, confidence: f64) {
+        self.feeds.lock().unwrap().entry(symbol.to_string()).or_default().confidence = confidence;
+    }
+
+    pub fn set_volatility(&self, symbol: &str, volatility: f64) {
+        self.feeds.lock().unwrap().entry(symbol.to_string()).or_default().volatility = volatility;
+    }
+
+    pub fn set_smile(&self, symbol: &str, smile: VolSmile) {
+        self.feeds.lock().unwrap().entry(symbol.to_string()).or_default().smile = Some(smile);
+    }
+
+    /// Delays every price fetch by `latency`, e.g. to keep fetches in flight concurrently.
+    pub fn set_latency(&self, latency: Duration) {
+        *self.latency.lock().unwrap() = latency;
+    }
+
+    /// Makes every price fetch for `symbol` fail with `message` until cleared.
+    pub fn fail_price(&self, symbol: &str, message: &str) {
+        self.feeds.lock().unwrap().entry(symbol.to_string()).or_default().price_error = Some(message.to_string());
+    }
+
+    /// Makes every volatility fetch for `symbol` fail with `message` until cleared.
+    pub fn fail_volatility(&self, symbol: &str, message: &str) {
+        self.feeds.lock().unwrap().entry(symbol.to_string()).or_default().volatility_error = Some(message.to_string());
+    }
+
+    pub fn clear_failures(&self, symbol: &str) {
+        if let Some(feed) = self.feeds.lock().unwrap().get_mut(symbol) {
+            feed.price_error = None;
+            feed.volatility_error = None;
+        }
+    }
+
+    /// Number of `stream_prices` streams for `symbol` still open.
+    pub fn stream_subscribers(&self, symbol: &str) -> usize {
+        self.tick_sender(symbol).receiver_count()
+    }
+
+    /// Number of `fetch_price` calls served so far, including failed ones.
+    pub fn price_fetches(&self) -> usize {
+        self.price_fetches.load(Ordering::SeqCst)
+    }
+
+    fn tick_sender(&self, symbol: &str) -> broadcast::Sender<PriceData> {
+        self.ticks.lock().unwrap()
+            .entry(symbol.to_string())
+            .or_insert_with(|| broadcast::channel(16).0)
+            .clone()
+    }
+
+    fn price_data(&self, symbol: &str, price: f64) -> PriceData {
+        PriceData {
+            symbol: symbol.to_string(),
+            price,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64,
+            confidence: self.feeds.lock().unwrap().get(symbol).map_or(0.0, |feed| feed.confidence),
+            expo: None,
+        }
+    }
+
+    fn feed(&self, symbol: &str) -> Result<MockFeed> {
+        self.feeds.lock().unwrap().get(symbol).cloned()
+            .ok_or_else(|| EngineError::UnknownSymbol(symbol.to_string()).into())
+    }
+}
+
+#[async_trait]
+impl PriceOracle for MockOracle {
+    fn symbols(&self) -> Vec<String> {
+        self.feeds.lock().unwrap().keys().cloned().collect()
+    }
+
+    fn resolve_symbol(&self, symbol: &str) -> String {
+        self.aliases.get(symbol).cloned().unwrap_or_else(|| symbol.to_string())
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<PriceData> {
+        self.price_fetches.fetch_add(1, Ordering::SeqCst);
+        let latency = *self.latency.lock().unwrap();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        let feed = self.feed(symbol)?;
+        if let Some(message) = feed.price_error {
+            anyhow::bail!(message);
+        }
+
+        Ok(self.price_data(symbol, feed.price))
+    }
+
+    async fn fetch_volatility(&self, symbol: &str) -> Result<f64> {
+        let feed = self.feed(symbol)?;
+        if let Some(message) = feed.volatility_error {
+            anyhow::bail!(message);
+        }
+        Ok(feed.volatility)
+    }
+
+    fn volatility_smile(&self, symbol: &str) -> Option<VolSmile> {
+        self.feeds.lock().unwrap().get(symbol).and_then(|feed| feed.smile.clone())
+    }
+
+    // Yields whatever `push_price` sends from now on
+    async fn stream_prices(&self, symbol: &str) -> Result<BoxStream<'static, Result<PriceData>>> {
+        self.feed(symbol)?;
+        let receiver = self.tick_sender(symbol).subscribe();
+        Ok(stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(price) => return Some((Ok(price), receiver)),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_serves_configured_values_and_failures() {
+        let oracle = MockOracle::new().with_price("SUI", 2.0, 0.5);
+        assert_eq!(oracle.fetch_price("SUI").await.unwrap().price, 2.0);
+        assert_eq!(oracle.fetch_volatility("SUI").await.unwrap(), 0.5);
+
+        oracle.fail_price("SUI", "hermes down");
+        let err = oracle.fetch_price("SUI").await.unwrap_err();
+        assert_eq!(err.to_string(), "hermes down");
+
+        oracle.clear_failures("SUI");
+        assert!(oracle.fetch_price("SUI").await.is_ok());
+        assert_eq!(oracle.price_fetches(), 3);
+
+        let err = oracle.fetch_price("BTC").await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::UnknownSymbol(_))));
+    }
+}
@@ -0,0 +1,1067 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::future;
+use futures::stream::{BoxStream, Stream, StreamExt}; // For stream processing methods
+use pyth_hermes_client::{EncodingType, ParsedPriceUpdate, PriceUpdate, PythClient};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing::{Span, debug, field, instrument, warn};
+
+use crate::clock::Clock;
+use crate::config;
+use crate::error::EngineError;
+use crate::metrics::metrics;
+use crate::smile::VolSmile;
+use crate::verify::verify_update;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceData {
+    pub symbol: String,
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub price: f64,
+    pub timestamp: i64,
+    // Pyth's confidence interval, in the same units as `price`
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub confidence: f64,
+    // Decimal exponent of the Pyth feed the price was scaled from; None for other sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expo: Option<i32>,
+}
+
+impl PriceData {
+    /// Takes the `source` price from a Pyth update and scales its integer price and confidence
+    /// by its exponent, so both come out in the same units, keeping the exponent alongside.
+    pub fn from_pyth(symbol: String, price_feed: &ParsedPriceUpdate, source: PriceSource) -> Self {
+        let price = match source {
+            PriceSource::Latest => &price_feed.price,
+            PriceSource::Ema => &price_feed.ema_price,
+        };
+        let scale = 10.0_f64.powi(price.expo);
+        Self {
+            symbol,
+            price: price.price as f64 * scale,
+            timestamp: price.publish_time,
+            confidence: price.conf as f64 * scale,
+            expo: Some(price.expo),
+        }
+    }
+
+    /// Confidence interval as a fraction of the price; infinite for a non-positive price.
+    pub fn confidence_ratio(&self) -> f64 {
+        if self.price > 0.0 { self.confidence / self.price } else { f64::INFINITY }
+    }
+}
+
+/// Which of the two prices in every Pyth update to price off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSource {
+    /// The latest aggregate price
+    #[default]
+    Latest,
+    /// Pyth's exponentially weighted moving average of it, which smooths out tick noise
+    Ema,
+}
+
+/// Encoding Hermes sends each update's binary payload in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// What the oracle asks Hermes for on every price stream, and whether it checks what comes back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamOptions {
+    /// Every update carries the binary payload a Pyth contract accepts on-chain: the
+    /// Wormhole-signed VAA and the Merkle proofs of each price under it. This picks its encoding.
+    pub encoding: UpdateEncoding,
+    /// Ask for each update's prices parsed out as JSON as well. Pricing reads only the parsed
+    /// prices, so this can't be turned off.
+    pub parsed: bool,
+    /// Let Hermes send an update whose publish time is older than one already sent, instead
+    /// of dropping it. Off, every stream moves forward in time.
+    pub allow_unordered: bool,
+    /// Only send the first update at each publish time, the one benchmarks are built from,
+    /// and skip repeats of the same price.
+    pub benchmarks_only: bool,
+    /// Refuse updates whose parsed prices don't match the price messages in their binary
+    /// payload. The VAA's guardian signatures aren't checked here: that is done by the
+    /// contract the payload is posted to, so this guards against a relay whose parsed
+    /// prices differ from what would settle on-chain.
+    pub verify: bool,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self {
+            encoding: UpdateEncoding::Hex,
+            parsed: true,
+            allow_unordered: false,
+            benchmarks_only: true,
+            verify: false,
+        }
+    }
+}
+
+// How long `fetch_prices` waits for every requested feed to send an update
+pub const MULTI_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Updates without parsed prices `fetch_pyth_price_real` reads before giving up with
+// `EngineError::NoParsedData`, so a stream that never parses can't keep it waiting
+pub const MAX_UNPARSED_UPDATES: usize = 10;
+
+// Updates failing verification a fetch skips before giving up with the last one's
+// `EngineError::UnverifiedUpdate`; one bad update shouldn't fail a fetch the next would answer
+pub const MAX_UNVERIFIED_UPDATES: usize = 10;
+
+// Names callers commonly use for a feed's ticker; matched after upper-casing
+const SYMBOL_ALIASES: &[(&str, &str)] = &[
+    ("BITCOIN", "BTC"),
+    ("XBT", "BTC"),
+    ("ETHER", "ETH"),
+    ("ETHEREUM", "ETH"),
+    ("SOLANA", "SOL"),
+];
+
+// How long a health check waits on each feed before calling it unreachable
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether one configured feed answered, and how old its latest price is.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedHealth {
+    pub symbol: String,
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_secs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A Pyth feed as Hermes lists it, found with [`PythOracle::discover_feeds`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedInfo {
+    // 0x-prefixed, ready for `register_feed`
+    pub id: String,
+    // Pyth's full name for the feed, e.g. "Crypto.SUI/USD"
+    pub symbol: String,
+    // The base asset, e.g. "SUI"; what `register_discovered` registers the feed under
+    pub ticker: String,
+    // Decimal exponent of the feed's prices; None if Hermes sent no price to read it from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expo: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub checked_at: i64,
+    pub feeds: Vec<FeedHealth>,
+}
+
+impl HealthReport {
+    // Errors when no feed answered at all, since then it's the endpoint that's down
+    fn from_results(results: Vec<(String, Result<PriceData>)>, clock: &dyn Clock) -> Result<Self> {
+        let checked_at = clock.now_secs() as i64;
+        let mut first_error = None;
+        let feeds: Vec<FeedHealth> = results
+            .into_iter()
+            .map(|(symbol, result)| match result {
+                Ok(price) => FeedHealth {
+                    symbol,
+                    reachable: true,
+                    publish_time: Some(price.timestamp),
+                    age_secs: Some(checked_at - price.timestamp),
+                    error: None,
+                },
+                Err(err) => {
+                    let error = format!("{:#}", err);
+                    first_error.get_or_insert_with(|| error.clone());
+                    FeedHealth { symbol, reachable: false, publish_time: None, age_secs: None, error: Some(error) }
+                }
+            })
+            .collect();
+
+        if !feeds.iter().any(|feed| feed.reachable) {
+            let reason = first_error.unwrap_or_else(|| "no feeds configured".to_string());
+            return Err(anyhow::anyhow!("Oracle unreachable: {}", reason));
+        }
+        Ok(Self { checked_at, feeds })
+    }
+
+    /// Every feed answered with a price published within `max_age_secs`.
+    pub fn is_healthy(&self, max_age_secs: u64) -> bool {
+        self.feeds.iter().all(|feed| feed.age_secs.is_some_and(|age| age <= max_age_secs as i64))
+    }
+}
+
+// Source of underlying prices and volatility for the engine
+#[async_trait]
+pub trait PriceOracle: Send + Sync {
+    /// Symbols this oracle can price.
+    fn symbols(&self) -> Vec<String>;
+
+    /// The symbol this oracle files `symbol` under, which the engine keys its config, caches
+    /// and subscriptions on. Spelled as given unless the oracle knows other names for it.
+    fn resolve_symbol(&self, symbol: &str) -> String {
+        symbol.to_string()
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<PriceData>;
+
+    async fn fetch_volatility(&self, symbol: &str) -> Result<f64>;
+
+    /// Symbol -> feed id for oracles backed by identified feeds; empty for the rest.
+    fn feed_ids(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Strike-dependent vol for `symbol`, if one is configured; pricing falls back to `fetch_volatility`.
+    fn volatility_smile(&self, _symbol: &str) -> Option<VolSmile> {
+        None
+    }
+
+    /// Continuous price updates for `symbol`; oracles without a push feed don't support this.
+    async fn stream_prices(&self, symbol: &str) -> Result<BoxStream<'static, Result<PriceData>>> {
+        Err(anyhow::anyhow!("Price streaming is not supported by this oracle (symbol: {})", symbol))
+    }
+
+    /// Fetches every symbol once, giving up on each after `HEALTH_CHECK_TIMEOUT`. `clock`
+    /// dates the report and the ages of the prices in it.
+    async fn health_check(&self, clock: &dyn Clock) -> Result<HealthReport> {
+        let checks = self.symbols().into_iter().map(|symbol| async move {
+            let result = match timeout(HEALTH_CHECK_TIMEOUT, self.fetch_price(&symbol)).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow::anyhow!("timed out after {:?}", HEALTH_CHECK_TIMEOUT)),
+            };
+            (symbol, result)
+        });
+        HealthReport::from_results(future::join_all(checks).await, clock)
+    }
+}
+
+// Where the oracle connects and which feeds it knows about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OracleConfig {
+    pub hermes_url: String,
+    pub feeds: HashMap<String, String>, // symbol -> feed_id
+    // symbol -> [[strike, vol], ...]; symbols without one are priced at a flat vol
+    pub smiles: HashMap<String, VolSmile>,
+    // latest or ema
+    pub price_source: PriceSource,
+    // Options sent with every Hermes price stream, under `[oracle.stream]`
+    pub stream: StreamOptions,
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        let mut feeds = HashMap::new();
+
+        // // Example Pyth price feed IDs (these are examples, use actual ones)
+        // feeds.insert("BTC".to_string(), "0xe62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43".to_string());
+        // feeds.insert("ETH".to_string(), "0xff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace".to_string());
+        feeds.insert("SUI".to_string(), "0x23d7315113f5b1d3ba7a83604c44b94d79f4fd69af77f804fc7f920a6dc65744".to_string());
+        Self {
+            hermes_url: "https://hermes.pyth.network".to_string(),
+            feeds,
+            smiles: HashMap::new(),
+            price_source: PriceSource::Latest,
+            stream: StreamOptions::default(),
+        }
+    }
+}
+
+// The parsed prices in `update`, once checked against its binary payload if `verify` is on
+fn trusted(update: &PriceUpdate, verify: bool) -> Result<&[ParsedPriceUpdate]> {
+    let parsed = update.parsed.as_deref().unwrap_or_default();
+    if verify && let Err(reason) = verify_update(update) {
+        let feed_id = parsed.iter().map(|feed| feed.id.as_str()).collect::<Vec<_>>().join(",");
+        return Err(EngineError::UnverifiedUpdate { feed_id, reason }.into());
+    }
+    Ok(parsed)
+}
+
+/// Checks that `feed_id` is a `0x`-prefixed, 64 hex digit Pyth feed id and returns its bytes.
+pub fn validate_feed_id(feed_id: &str) -> Result<[u8; 32]> {
+    let invalid = |reason: &str| EngineError::InvalidParams(format!("feed id {} {}", feed_id, reason));
+    let hex = feed_id.strip_prefix("0x").ok_or_else(|| invalid("must start with 0x"))?;
+    if hex.len() != 64 {
+        return Err(invalid(&format!("must have 64 hex digits, got {}", hex.len())).into());
+    }
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(invalid("is not hex").into());
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(bytes)
+}
+
+// Config files keep the oracle settings under an `[oracle]` table next to the engine settings
+#[derive(Deserialize)]
+struct OracleSection {
+    #[serde(default)]
+    oracle: OracleConfig,
+}
+
+// Pyth Oracle Provider for Sui
+#[derive(Debug)]
+pub struct PythOracle {
+    // In a real implementation, you'd have Sui client and Pyth price feed IDs
+    price_feeds: HashMap<String, String>, // symbol -> feed_id
+    smiles: HashMap<String, VolSmile>,
+    price_source: PriceSource,
+    stream: StreamOptions,
+    client: PythClient,
+}
+
+impl Default for PythOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PythOracle {
+    pub fn new() -> Self {
+        Self::with_config(OracleConfig::default()).expect("default oracle config is valid")
+    }
+
+    pub fn with_config(config: OracleConfig) -> Result<Self> {
+        let url = config.hermes_url.parse()
+            .with_context(|| format!("Invalid hermes_url: {}", config.hermes_url))?;
+        for (symbol, feed_id) in &config.feeds {
+            validate_feed_id(feed_id).with_context(|| format!("Invalid feed for {}", symbol))?;
+        }
+        if !config.stream.parsed {
+            return Err(EngineError::InvalidParams("stream.parsed must be on, prices are read from the parsed updates".to_string()).into());
+        }
+        Ok(Self {
+            price_feeds: config.feeds,
+            smiles: config.smiles,
+            price_source: config.price_source,
+            stream: config.stream,
+            client: PythClient::new(url),
+        })
+    }
+
+    /// Loads the `[oracle]` table of a TOML or JSON config file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let section: OracleSection = config::load(path)?;
+        Self::with_config(section.oracle)
+    }
+
+    /// Adds or replaces the feed behind `symbol`, rejecting malformed feed ids.
+    pub fn register_feed(&mut self, symbol: &str, feed_id: &str) -> Result<()> {
+        validate_feed_id(feed_id).with_context(|| format!("Invalid feed for {}", symbol))?;
+        self.price_feeds.insert(symbol.to_string(), feed_id.to_string());
+        Ok(())
+    }
+
+    /// Registers a feed found with `discover_feeds` under its ticker.
+    pub fn register_discovered(&mut self, feed: &FeedInfo) -> Result<()> {
+        self.register_feed(&feed.ticker, &feed.id)
+    }
+
+    /// Feeds whose Pyth symbol contains `query` (case-insensitive, e.g. "sui"), from Hermes'
+    /// feed listing, with each feed's exponent read off its latest price.
+    pub async fn discover_feeds(&self, query: &str) -> Result<Vec<FeedInfo>> {
+        let listed = self.client.price_feeds(query.to_string(), None).await
+            .with_context(|| format!("Failed to list Hermes feeds matching {:?}", query))?;
+        let mut feeds: Vec<FeedInfo> = listed.into_iter()
+            .map(|feed| {
+                let attribute = |key: &str| feed.attributes.get(key).cloned().unwrap_or_default();
+                FeedInfo {
+                    id: format!("0x{}", feed.id.trim_start_matches("0x")),
+                    symbol: attribute("symbol"),
+                    ticker: attribute("base"),
+                    expo: None,
+                }
+            })
+            .collect();
+        if feeds.is_empty() {
+            return Ok(feeds);
+        }
+
+        // The listing has no exponents, so ask for every feed's latest price in one go. The
+        // ids are still worth having without them, hence only a warning on failure
+        let ids = feeds.iter().map(|feed| feed.id.clone()).collect();
+        match self.client.latest_price_update(ids, None, Some(true)).await {
+            Ok(update) => {
+                for price_feed in update.parsed.iter().flatten() {
+                    if let Some(feed) = feeds.iter_mut().find(|feed| same_feed(&feed.id, &price_feed.id)) {
+                        feed.expo = Some(price_feed.price.expo);
+                    }
+                }
+            }
+            Err(err) => warn!(query, error = ?err, "Failed to read exponents of discovered feeds"),
+        }
+        Ok(feeds)
+    }
+
+    /// Feed id registered for `symbol`, if any. See [`PythOracle::configured_symbol`] for the
+    /// spellings that are accepted.
+    pub fn feed_id(&self, symbol: &str) -> Option<&str> {
+        let symbol = self.configured_symbol(symbol)?;
+        self.price_feeds.get(symbol).map(String::as_str)
+    }
+
+    /// Configured symbol that `symbol` refers to, ignoring case, a `/USD` or `-USD` quote
+    /// suffix and common names like "bitcoin".
+    pub fn configured_symbol(&self, symbol: &str) -> Option<&str> {
+        let mut normalized = symbol.trim().to_ascii_uppercase();
+        for suffix in ["/USD", "-USD"] {
+            if let Some(base) = normalized.strip_suffix(suffix) {
+                normalized = base.to_string();
+            }
+        }
+        let canonical = SYMBOL_ALIASES.iter()
+            .find(|(alias, _)| *alias == normalized)
+            .map_or(normalized.as_str(), |(_, ticker)| ticker);
+        self.price_feeds.keys()
+            .find(|key| key.eq_ignore_ascii_case(canonical))
+            .map(String::as_str)
+    }
+
+    // Opens a stream just to read one update; the engine's background updates keep a
+    // long-lived subscription instead and only use this for cold starts
+    #[instrument(skip(self), fields(latency_ms = field::Empty))]
+    pub async fn fetch_pyth_price_real(&self, feed_id: &str) -> Result<PriceData> {
+        let timer = metrics().oracle_fetch_seconds.start_timer();
+        let result = self.fetch_first_update(feed_id).await;
+        let latency = timer.stop_and_record();
+        Span::current().record("latency_ms", (latency * 1000.0) as u64);
+        match &result {
+            Ok(price) => debug!(price = price.price, publish_time = price.timestamp, "Fetched oracle price"),
+            Err(err) => {
+                metrics().oracle_fetch_failures.inc();
+                warn!(error = %format!("{:#}", err), "Oracle fetch failed");
+            }
+        }
+        result
+    }
+
+    // A Hermes price stream for `feed_ids` with the configured `StreamOptions`
+    async fn open_stream(
+        &self,
+        feed_ids: Vec<String>,
+    ) -> Result<impl Stream<Item = Result<PriceUpdate, pyth_hermes_client::Error>> + Send + use<>> {
+        let encoding = match self.stream.encoding {
+            UpdateEncoding::Hex => EncodingType::Hex,
+            UpdateEncoding::Base64 => EncodingType::Base64,
+        };
+        let stream = self.client.stream_price_updates(
+            feed_ids,
+            Some(encoding),
+            Some(self.stream.parsed),
+            Some(self.stream.allow_unordered),
+            Some(self.stream.benchmarks_only),
+        ).await?;
+        Ok(stream)
+    }
+
+    async fn fetch_first_update(&self, feed_id: &str) -> Result<PriceData> {
+        let mut price_updates = std::pin::pin!(self.open_stream(vec![feed_id.to_string()]).await?);
+
+        // Process the stream until we get the first valid price
+        let (mut unparsed, mut unverified) = (0, 0);
+        loop {
+            match price_updates.next().await {
+                Some(Ok(price_update)) => {
+                    debug!(feed_id, "Received price update");
+
+                    let parsed = match trusted(&price_update, self.stream.verify) {
+                        Ok(parsed) => parsed,
+                        Err(err) => {
+                            unverified += 1;
+                            if unverified == MAX_UNVERIFIED_UPDATES {
+                                return Err(err);
+                            }
+                            warn!(feed_id, error = %err, "Skipping price update");
+                            continue;
+                        }
+                    };
+                    // Access parsed data if available
+                    if let Some(price_feed) = parsed.first() {
+                        // Return the first valid price data we receive
+                        return Ok(PriceData::from_pyth(self.symbol_for_feed(feed_id), price_feed, self.price_source));
+                    }
+                    unparsed += 1;
+                    if unparsed == MAX_UNPARSED_UPDATES {
+                        return Err(EngineError::NoParsedData { feed_id: feed_id.to_string(), updates: unparsed }.into());
+                    }
+                },
+                Some(Err(err)) => {
+                    warn!(feed_id, error = ?err, "Error receiving price update");
+                    // Continue to next update instead of failing immediately
+                    continue;
+                },
+                None => {
+                    // Stream ended
+                    break;
+                }
+            }
+        }
+
+        // If we exit the loop without finding data
+        Err(anyhow::anyhow!("Failed to fetch price data - stream ended without valid data"))
+    }
+
+    /// Latest price for each of `feed_ids`, keyed by feed id, from a single Hermes stream.
+    /// Feeds that send nothing within `MULTI_FETCH_TIMEOUT` are left out of the map; it is
+    /// an error only when none of them answer.
+    pub async fn fetch_prices(&self, feed_ids: &[String]) -> Result<HashMap<String, PriceData>> {
+        self.fetch_prices_within(feed_ids, MULTI_FETCH_TIMEOUT).await
+    }
+
+    async fn fetch_prices_within(&self, feed_ids: &[String], within: Duration) -> Result<HashMap<String, PriceData>> {
+        let mut prices = HashMap::new();
+        if feed_ids.is_empty() {
+            return Ok(prices);
+        }
+        let collect = async {
+            let mut price_updates = std::pin::pin!(self.open_stream(feed_ids.to_vec()).await?);
+            let mut unverified = 0;
+            while prices.len() < feed_ids.len() {
+                match price_updates.next().await {
+                    Some(Ok(price_update)) => {
+                        let parsed = match trusted(&price_update, self.stream.verify) {
+                            Ok(parsed) => parsed,
+                            Err(err) => {
+                                unverified += 1;
+                                if unverified == MAX_UNVERIFIED_UPDATES {
+                                    return Err(err);
+                                }
+                                warn!(error = %err, "Skipping price update");
+                                continue;
+                            }
+                        };
+                        for price_feed in parsed {
+                            // Hermes reports ids without the 0x prefix
+                            if let Some(feed_id) = feed_ids.iter().find(|id| same_feed(id, &price_feed.id)) {
+                                prices.entry(feed_id.clone())
+                                    .or_insert_with(|| PriceData::from_pyth(self.symbol_for_feed(feed_id), price_feed, self.price_source));
+                            }
+                        }
+                    }
+                    Some(Err(err)) => warn!(error = ?err, "Error receiving price update"),
+                    None => break,
+                }
+            }
+            anyhow::Ok(())
+        };
+        let outcome = timeout(within, collect).await;
+
+        let missing: Vec<&str> = feed_ids.iter()
+            .filter(|id| !prices.contains_key(*id))
+            .map(String::as_str)
+            .collect();
+        if prices.is_empty() {
+            return match outcome {
+                Ok(Err(err)) => Err(err.context("Failed to fetch any of the requested feeds")),
+                Ok(Ok(())) => Err(anyhow::anyhow!("Price stream ended before any feed sent data")),
+                Err(_) => Err(anyhow::anyhow!("No feed sent a price within {:?}", within)),
+            };
+        }
+        if !missing.is_empty() {
+            warn!(?missing, "Some feeds sent no price in time");
+        }
+        Ok(prices)
+    }
+
+    // Long-lived subscription yielding every parsed update for `feed_id`
+    pub async fn stream_pyth_prices(
+        &self,
+        feed_id: &str,
+    ) -> Result<impl Stream<Item = Result<PriceData>> + Send + use<>> {
+        let price_updates = self.open_stream(vec![feed_id.to_string()]).await?;
+
+        let symbol = self.symbol_for_feed(feed_id);
+        let source = self.price_source;
+        let verify = self.stream.verify;
+        Ok(price_updates.filter_map(move |update| {
+            let item = match update {
+                Ok(price_update) => match trusted(&price_update, verify) {
+                    Ok(parsed) => parsed.first().map(|price_feed| Ok(PriceData::from_pyth(symbol.clone(), price_feed, source))),
+                    Err(err) => Some(Err(err)),
+                },
+                Err(err) => Some(Err(err.into())),
+            };
+            future::ready(item)
+        }))
+    }
+
+    // One REST request per feed for its latest price, instead of opening streams
+    async fn check_feeds(&self, within: Duration, clock: &dyn Clock) -> Result<HealthReport> {
+        let checks = self.price_feeds.iter().map(|(symbol, feed_id)| async move {
+            let request = self.client.latest_price_update(vec![feed_id.clone()], None, Some(true));
+            let result = match timeout(within, request).await {
+                Ok(Ok(update)) => update.parsed.as_ref()
+                    .and_then(|parsed| parsed.first())
+                    .map(|price_feed| PriceData::from_pyth(symbol.clone(), price_feed, self.price_source))
+                    .ok_or_else(|| anyhow::anyhow!("no parsed price for feed {}", feed_id)),
+                Ok(Err(err)) => Err(err.into()),
+                Err(_) => Err(anyhow::anyhow!("timed out after {:?}", within)),
+            };
+            (symbol.clone(), result)
+        });
+        HealthReport::from_results(future::join_all(checks).await, clock)
+    }
+
+    fn symbol_for_feed(&self, feed_id: &str) -> String {
+        self.price_feeds.iter()
+            .find_map(|(key, val)| if *val == feed_id { Some(key.clone()) } else { None })
+            .unwrap_or_else(|| "UNKNOWN".to_string())
+    }
+
+    fn require_feed_id(&self, symbol: &str) -> Result<&str> {
+        self.feed_id(symbol)
+            .ok_or_else(|| EngineError::UnknownSymbol(symbol.to_string()).into())
+    }
+}
+
+#[async_trait]
+impl PriceOracle for PythOracle {
+    fn symbols(&self) -> Vec<String> {
+        self.price_feeds.keys().cloned().collect()
+    }
+
+    fn resolve_symbol(&self, symbol: &str) -> String {
+        self.configured_symbol(symbol).unwrap_or(symbol).to_string()
+    }
+
+    fn feed_ids(&self) -> HashMap<String, String> {
+        self.price_feeds.clone()
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<PriceData> {
+        let feed_id = self.require_feed_id(symbol)?;
+        self.fetch_pyth_price_real(feed_id).await
+    }
+
+    async fn fetch_volatility(&self, symbol: &str) -> Result<f64> {
+        // Mock volatility calculation - in practice you'd calculate from historical data
+        let base_vol = match self.configured_symbol(symbol).unwrap_or(symbol) {
+            "BTC" => 0.8,
+            "ETH" => 0.9,
+            "SUI" => 1.2,
+            _ => 1.0,
+        };
+
+        Ok(base_vol)
+    }
+
+    fn volatility_smile(&self, symbol: &str) -> Option<VolSmile> {
+        self.smiles.get(self.configured_symbol(symbol).unwrap_or(symbol)).cloned()
+    }
+
+    async fn stream_prices(&self, symbol: &str) -> Result<BoxStream<'static, Result<PriceData>>> {
+        let feed_id = self.require_feed_id(symbol)?;
+        Ok(self.stream_pyth_prices(feed_id).await?.boxed())
+    }
+
+    /// Asks Hermes for each configured feed's latest price, giving up on each after `HEALTH_CHECK_TIMEOUT`.
+    async fn health_check(&self, clock: &dyn Clock) -> Result<HealthReport> {
+        self.check_feeds(HEALTH_CHECK_TIMEOUT, clock).await
+    }
+}
+
+fn same_feed(configured: &str, reported: &str) -> bool {
+    configured.trim_start_matches("0x").eq_ignore_ascii_case(reported.trim_start_matches("0x"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+    use crate::config::tests::write_temp;
+
+    const BTC_FEED: &str = "0xe62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43";
+
+    #[test]
+    fn test_oracle_from_file() {
+        let path = write_temp("oracle.toml", r#"
+risk_free_rate = 0.04
+
+[oracle]
+hermes_url = "https://hermes-beta.pyth.network"
+
+[oracle.feeds]
+SUI = "0x23d7315113f5b1d3ba7a83604c44b94d79f4fd69af77f804fc7f920a6dc65744"
+ETH = "0xff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace"
+
+[oracle.smiles]
+SUI = [[1.5, 0.7], [2.0, 0.5], [2.5, 0.6]]
+"#);
+        let oracle = PythOracle::from_file(&path).unwrap();
+        assert_eq!(oracle.feed_id("ETH"), Some("0xff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace"));
+        assert!(oracle.feed_id("BTC").is_none());
+        assert_eq!(oracle.volatility_smile("SUI").unwrap().vol_at(1.5), 0.7);
+        assert!(oracle.volatility_smile("ETH").is_none());
+    }
+
+    #[test]
+    fn test_price_data_json_roundtrip() {
+        let price = PriceData { symbol: "SUI".to_string(), price: 2.89123456789, timestamp: 1_700_000_000, confidence: 0.00123, expo: None };
+        let json = serde_json::to_string(&price).unwrap();
+        assert_eq!(serde_json::from_str::<PriceData>(&json).unwrap(), price);
+
+        assert!(serde_json::to_string(&PriceData { price: f64::INFINITY, ..price }).is_err());
+    }
+
+    #[test]
+    fn test_from_pyth_scales_price_and_confidence_alike() {
+        let update: ParsedPriceUpdate = serde_json::from_value(serde_json::json!({
+            "id": BTC_FEED.trim_start_matches("0x"),
+            "price": { "price": "6500012345678", "conf": "3250000000", "expo": -8, "publish_time": 1_700_000_000 },
+            "ema_price": { "price": "6500000000000", "conf": "3000000000", "expo": -8, "publish_time": 1_700_000_000 },
+            "metadata": {},
+        }))
+        .unwrap();
+        let price = PriceData::from_pyth("BTC".to_string(), &update, PriceSource::Latest);
+        assert!((price.price - 65_000.12345678).abs() < 1e-6);
+        // 32.5 dollars, not 3.25e9 raw units: half a basis point of the price
+        assert!((price.confidence - 32.5).abs() < 1e-9);
+        assert!((price.confidence_ratio() - 0.0005).abs() < 1e-6);
+        assert_eq!((price.timestamp, price.expo), (1_700_000_000, Some(-8)));
+
+        let ema = PriceData::from_pyth("BTC".to_string(), &update, PriceSource::Ema);
+        assert_eq!((ema.price, ema.confidence), (65_000.0, 30.0));
+    }
+
+    #[test]
+    fn test_validate_feed_id() {
+        let sui = validate_feed_id("0x23d7315113f5b1d3ba7a83604c44b94d79f4fd69af77f804fc7f920a6dc65744").unwrap();
+        assert_eq!(sui[0], 0x23);
+        assert_eq!(sui[31], 0x44);
+
+        // The truncated BTC id that used to sit in `OracleConfig::default`
+        assert!(validate_feed_id("0xe62df6c8b4c85fe1c755c63f0e2e6a1e8b8d8a2d").is_err());
+        assert!(validate_feed_id(BTC_FEED.trim_start_matches("0x")).is_err());
+        assert!(validate_feed_id(&BTC_FEED.replace('e', "g")).is_err());
+        assert!(validate_feed_id(&format!("0x{}", "é".repeat(32))).is_err());
+
+        let mut oracle = PythOracle::new();
+        let err = oracle.register_feed("BTC", "0xe62df6c8b4c85fe1c755c63f0e2e6a1e8b8d8a2d").unwrap_err();
+        assert!(err.to_string().contains("BTC"), "{}", err);
+        assert!(oracle.feed_id("BTC").is_none());
+
+        let mut config = OracleConfig::default();
+        config.feeds.insert("BTC".to_string(), "0x1234".to_string());
+        assert!(PythOracle::with_config(config).is_err());
+    }
+
+    #[test]
+    fn test_symbol_aliases_resolve_to_configured_feed() {
+        let mut oracle = PythOracle::new();
+        oracle.register_feed("BTC", BTC_FEED).unwrap();
+
+        for symbol in ["BTC", "btc", "BTC/USD", "btc-usd", " Bitcoin ", "xbt/usd"] {
+            assert_eq!(oracle.feed_id(symbol), Some(BTC_FEED), "{}", symbol);
+        }
+        assert_eq!(oracle.configured_symbol("sui/usd"), Some("SUI"));
+        assert_eq!(oracle.resolve_symbol("Bitcoin"), "BTC");
+        assert_eq!(oracle.resolve_symbol("doge/usd"), "doge/usd");
+        // Aliases only map onto feeds that are actually configured
+        assert!(oracle.feed_id("ethereum").is_none());
+        assert!(oracle.feed_id("USD").is_none());
+
+        let err = oracle.require_feed_id("doge/usd").unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::UnknownSymbol(s)) if s == "doge/usd"));
+    }
+
+    #[test]
+    fn test_oracle_from_file_defaults_and_errors() {
+        // No [oracle] table at all keeps the built-in feeds
+        let path = write_temp("oracle_empty.toml", "risk_free_rate = 0.04\n");
+        assert!(PythOracle::from_file(&path).unwrap().feed_id("SUI").is_some());
+
+        let path = write_temp("oracle_bad.json", r#"{ "oracle": { "feeds": { "SUI": 42 } } }"#);
+        let err = PythOracle::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("oracle.feeds.SUI"), "{}", err);
+
+        let path = write_temp("oracle_bad_url.toml", "[oracle]\nhermes_url = \"not a url\"\n");
+        assert!(PythOracle::from_file(&path).is_err());
+    }
+
+    // Serves one SSE response with an update per `(feed_id, price)`, then holds the stream open
+    async fn fake_hermes_stream(updates: Vec<(String, i64)>) -> String {
+        let events = updates.into_iter()
+            .map(|(feed_id, price)| {
+                let parsed = serde_json::json!({
+                    "id": feed_id.trim_start_matches("0x"),
+                    "price": { "price": price.to_string(), "conf": "10", "expo": -3, "publish_time": 1_700_000_000 },
+                    "ema_price": { "price": price.to_string(), "conf": "10", "expo": -3, "publish_time": 1_700_000_000 },
+                    "metadata": {},
+                });
+                serde_json::json!({ "binary": { "encoding": "hex", "data": [] }, "parsed": [parsed] })
+            })
+            .collect();
+        fake_hermes_events(events).await.0
+    }
+
+    // Serves `events` as one SSE response and hands back the request line it was asked with
+    async fn fake_hermes_events(events: Vec<serde_json::Value>) -> (String, tokio::sync::oneshot::Receiver<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (request_tx, request_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let read = socket.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..read]);
+            let _ = request_tx.send(request.lines().next().unwrap_or_default().to_string());
+            let mut response = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\r\n".to_string();
+            for event in events {
+                response.push_str(&format!("data: {}\n\n", event));
+            }
+            socket.write_all(response.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+        (format!("http://{}", addr), request_rx)
+    }
+
+    // Answers each of `responses` in turn with a JSON body, one connection apiece, and hands
+    // back the request lines
+    async fn fake_hermes_json(responses: Vec<serde_json::Value>) -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (request_tx, request_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for body in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 4096];
+                let read = socket.read(&mut request).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]);
+                let _ = request_tx.send(request.lines().next().unwrap_or_default().to_string());
+                let body = body.to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{}", addr), request_rx)
+    }
+
+    #[tokio::test]
+    async fn test_discovered_feeds_register_directly() {
+        let sui = OracleConfig::default().feeds["SUI"].trim_start_matches("0x").to_string();
+        let listing = serde_json::json!([{
+            "id": sui,
+            "attributes": { "symbol": "Crypto.SUI/USD", "base": "SUI", "quote_currency": "USD", "asset_type": "Crypto" },
+        }]);
+        let latest = serde_json::json!({
+            "binary": { "encoding": "hex", "data": [] },
+            "parsed": [{
+                "id": sui,
+                "price": { "price": "25000", "conf": "10", "expo": -4, "publish_time": 1_700_000_000 },
+                "ema_price": { "price": "25000", "conf": "10", "expo": -4, "publish_time": 1_700_000_000 },
+                "metadata": {},
+            }],
+        });
+        let (hermes_url, mut requests) = fake_hermes_json(vec![listing, latest]).await;
+        let config = OracleConfig { hermes_url, feeds: HashMap::new(), ..OracleConfig::default() };
+        let mut oracle = PythOracle::with_config(config).unwrap();
+
+        let feeds = oracle.discover_feeds("sui").await.unwrap();
+        assert!(requests.recv().await.unwrap().starts_with("GET /v2/price_feeds?query=sui"));
+        assert_eq!(feeds.len(), 1);
+        assert_eq!((feeds[0].symbol.as_str(), feeds[0].ticker.as_str(), feeds[0].expo), ("Crypto.SUI/USD", "SUI", Some(-4)));
+
+        oracle.register_discovered(&feeds[0]).unwrap();
+        assert_eq!(oracle.feed_id("sui/usd"), Some(format!("0x{}", sui).as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_fetched_confidence_is_in_price_units() {
+        // Raw price 2500 and conf 10 at expo -3: $2.50 give or take a cent
+        let sui = OracleConfig::default().feeds["SUI"].clone();
+        let hermes_url = fake_hermes_stream(vec![(sui.clone(), 2_500)]).await;
+        let oracle = PythOracle::with_config(OracleConfig { hermes_url, ..OracleConfig::default() }).unwrap();
+
+        let price = oracle.fetch_pyth_price_real(&sui).await.unwrap();
+        assert_eq!(price.price, 2.5);
+        assert!((price.confidence - 0.01).abs() < 1e-12, "{}", price.confidence);
+        assert!((price.confidence_ratio() - 0.004).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_ema_price_source() {
+        let sui = OracleConfig::default().feeds["SUI"].clone();
+        let update = serde_json::json!({
+            "id": sui.trim_start_matches("0x"),
+            "price": { "price": "2500", "conf": "10", "expo": -3, "publish_time": 1_700_000_001 },
+            "ema_price": { "price": "2400", "conf": "8", "expo": -3, "publish_time": 1_700_000_000 },
+            "metadata": {},
+        });
+        let (hermes_url, _) = fake_hermes_events(vec![serde_json::json!({ "binary": { "encoding": "hex", "data": [] }, "parsed": [update] })]).await;
+        let config = OracleConfig { hermes_url, price_source: PriceSource::Ema, ..OracleConfig::default() };
+        let oracle = PythOracle::with_config(config).unwrap();
+
+        let price = oracle.fetch_pyth_price_real(&sui).await.unwrap();
+        assert_eq!((price.price, price.timestamp), (2.4, 1_700_000_000));
+        assert_eq!(toml::from_str::<OracleConfig>("").unwrap().price_source, PriceSource::Latest);
+    }
+
+    #[tokio::test]
+    async fn test_stream_options_and_verification() {
+        use crate::verify::{PriceMessage, encode_update};
+
+        let sui = OracleConfig::default().feeds["SUI"].clone();
+        let message = PriceMessage {
+            feed_id: validate_feed_id(&sui).unwrap(),
+            price: 2_500,
+            conf: 10,
+            expo: -3,
+            publish_time: 1_700_000_000,
+            ema_price: 2_500,
+            ema_conf: 10,
+        };
+        let payload: String = encode_update(&[message]).iter().map(|byte| format!("{:02x}", byte)).collect();
+        let event = |price: &str| {
+            let parsed = serde_json::json!({
+                "id": sui.trim_start_matches("0x"),
+                "price": { "price": price, "conf": "10", "expo": -3, "publish_time": 1_700_000_000 },
+                "ema_price": { "price": "2500", "conf": "10", "expo": -3, "publish_time": 1_700_000_000 },
+                "metadata": {},
+            });
+            serde_json::json!({ "binary": { "encoding": "hex", "data": [payload] }, "parsed": [parsed] })
+        };
+        let stream = StreamOptions { allow_unordered: true, verify: true, ..StreamOptions::default() };
+
+        let (hermes_url, request) = fake_hermes_events(vec![event("2500")]).await;
+        let oracle = PythOracle::with_config(OracleConfig { hermes_url, stream, ..OracleConfig::default() }).unwrap();
+        assert_eq!(oracle.fetch_pyth_price_real(&sui).await.unwrap().price, 2.5);
+        let request = request.await.unwrap();
+        for flag in ["encoding=hex", "parsed=true", "allow_unordered=true", "benchmarks_only=true"] {
+            assert!(request.contains(flag), "{} missing from {}", flag, request);
+        }
+
+        // A parsed price the payload doesn't back is skipped, by single and multi-feed fetches alike
+        let verified = || async {
+            let (hermes_url, _) = fake_hermes_events(vec![event("9999"), event("2500")]).await;
+            PythOracle::with_config(OracleConfig { hermes_url, stream, ..OracleConfig::default() }).unwrap()
+        };
+        assert_eq!(verified().await.fetch_pyth_price_real(&sui).await.unwrap().price, 2.5);
+        assert_eq!(verified().await.fetch_prices(std::slice::from_ref(&sui)).await.unwrap()[&sui].price, 2.5);
+        // and both give up once MAX_UNVERIFIED_UPDATES in a row fail
+        let refused = || async {
+            let (hermes_url, _) = fake_hermes_events(vec![event("9999"); MAX_UNVERIFIED_UPDATES]).await;
+            PythOracle::with_config(OracleConfig { hermes_url, stream, ..OracleConfig::default() }).unwrap()
+        };
+        let err = refused().await.fetch_pyth_price_real(&sui).await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::UnverifiedUpdate { .. })), "{:#}", err);
+        let err = refused().await.fetch_prices(std::slice::from_ref(&sui)).await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::UnverifiedUpdate { .. })), "{:#}", err);
+        // Unverified, the same update goes through
+        let (hermes_url, _) = fake_hermes_events(vec![event("9999")]).await;
+        let oracle = PythOracle::with_config(OracleConfig { hermes_url, ..OracleConfig::default() }).unwrap();
+        assert_eq!(oracle.fetch_pyth_price_real(&sui).await.unwrap().price, 9.999);
+
+        let unparsed = StreamOptions { parsed: false, ..StreamOptions::default() };
+        assert!(PythOracle::with_config(OracleConfig { stream: unparsed, ..OracleConfig::default() }).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gives_up_on_unparsed_updates() {
+        // Hermes ignoring `parsed=true`: binary payloads only, and the stream stays open
+        let empty = serde_json::json!({ "binary": { "encoding": "hex", "data": [] }, "parsed": null });
+        let (hermes_url, request) = fake_hermes_events(vec![empty; MAX_UNPARSED_UPDATES]).await;
+        let oracle = PythOracle::with_config(OracleConfig { hermes_url, ..OracleConfig::default() }).unwrap();
+
+        let sui = oracle.price_feeds["SUI"].clone();
+        let err = tokio::time::timeout(Duration::from_secs(2), oracle.fetch_pyth_price_real(&sui)).await
+            .expect("fetch should fail instead of waiting on the open stream")
+            .unwrap_err();
+        match err.downcast_ref::<EngineError>() {
+            Some(EngineError::NoParsedData { feed_id, updates }) => {
+                assert_eq!(feed_id, &sui);
+                assert_eq!(*updates, MAX_UNPARSED_UPDATES);
+            }
+            other => panic!("expected NoParsedData, got {:?}", other),
+        }
+        assert!(request.await.unwrap().contains("parsed=true"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_prices_collects_one_update_per_feed() {
+        let sui = OracleConfig::default().feeds["SUI"].clone();
+        let hermes_url = fake_hermes_stream(vec![(sui.clone(), 2_500), (BTC_FEED.to_string(), 65_000_000)]).await;
+        let mut oracle = PythOracle::with_config(OracleConfig { hermes_url, ..OracleConfig::default() }).unwrap();
+        oracle.register_feed("BTC", BTC_FEED).unwrap();
+        let eth = "0xff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace".to_string();
+
+        let feed_ids = vec![sui.clone(), BTC_FEED.to_string(), eth.clone()];
+        let started = std::time::Instant::now();
+        let prices = oracle.fetch_prices_within(&feed_ids, Duration::from_millis(300)).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // ETH never answers and is left out rather than failing the batch
+        assert_eq!(prices.len(), 2);
+        assert!(!prices.contains_key(&eth));
+        assert_eq!(prices[&sui].symbol, "SUI");
+        assert_eq!(prices[&sui].price, 2.5);
+        assert!((prices[&sui].confidence - 0.01).abs() < 1e-12);
+        assert_eq!(prices[BTC_FEED].price, 65_000.0);
+
+        assert!(oracle.fetch_prices(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_times_out_on_dead_endpoint() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _server = tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        let config = OracleConfig { hermes_url: format!("http://{}", addr), ..OracleConfig::default() };
+        let oracle = PythOracle::with_config(config).unwrap();
+
+        let started = std::time::Instant::now();
+        let err = oracle.check_feeds(Duration::from_millis(100), &SystemClock).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(err.to_string().contains("timed out"), "{}", err);
+
+        let err = oracle.fetch_prices_within(&[oracle.feed_id("SUI").unwrap().to_string()], Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No feed sent a price"), "{}", err);
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live Hermes endpoint; run with --ignored"]
+    async fn test_oracle_health_check() {
+        let report = PythOracle::new().health_check(&SystemClock).await.unwrap();
+        assert!(report.feeds.iter().all(|feed| feed.reachable));
+        assert!(report.is_healthy(60));
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live Hermes endpoint; run with --ignored"]
+    async fn test_oracle_price_fetch() {
+        let oracle = PythOracle::new();
+        let price = oracle.fetch_pyth_price_real(oracle.price_feeds["SUI"].as_str()).await.unwrap();
+        assert_eq!(price.symbol, "SUI");
+        assert!(price.price > 0.0);
+        assert!(price.confidence > 0.0 && price.confidence_ratio() < 0.5);
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{Result, bail};
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::engine::PremiumResult;
+
+/// How premium results are written out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One pretty-printed JSON array
+    #[default]
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// `strike,type,premium,timestamp` with a header row
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            other => bail!("Unknown output format: {} (expected json, ndjson or csv)", other),
+        }
+    }
+}
+
+pub fn write_results(out: &mut impl Write, results: &[PremiumResult], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, results)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Ndjson => write_ndjson(out, results)?,
+        OutputFormat::Csv => write_csv(out, results)?,
+    }
+    Ok(())
+}
+
+pub fn write_csv(out: &mut impl Write, results: &[PremiumResult]) -> Result<()> {
+    writeln!(out, "strike,type,premium,timestamp")?;
+    for result in results {
+        let kind = if result.is_call { "call" } else { "put" };
+        writeln!(out, "{},{},{},{}", result.strike, kind, result.premium, result.timestamp)?;
+    }
+    Ok(())
+}
+
+pub fn write_ndjson(out: &mut impl Write, results: &[PremiumResult]) -> Result<()> {
+    for result in results {
+        serde_json::to_writer(&mut *out, result)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::VolSource;
+
+    fn result(strike: f64, is_call: bool, premium: f64) -> PremiumResult {
+        PremiumResult { symbol: "SUI".to_string(), strike, is_call, premium, prob_itm: 0.5, breakeven: strike, premium_in_underlying: None, notional_premium: premium, timestamp: 1_700_000_000, from_cache: false, vol_source: VolSource::Oracle, inputs: None }
+    }
+
+    #[test]
+    fn test_csv_and_ndjson_use_option_type() {
+        let results = vec![result(2.0, true, 0.25), result(2.0, false, 0.125)];
+
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &results).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "strike,type,premium,timestamp\n2,call,0.25,1700000000\n2,put,0.125,1700000000\n"
+        );
+
+        let mut ndjson = Vec::new();
+        write_results(&mut ndjson, &results, OutputFormat::Ndjson).unwrap();
+        let lines: Vec<PremiumResult> = String::from_utf8(ndjson).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].is_call && !lines[1].is_call);
+
+        assert_eq!("CSV".parse::<OutputFormat>().unwrap(), OutputFormat::Csv);
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::error::EngineError;
+use crate::pricing::Greeks;
+use crate::strategy::{Leg, Strategy};
+
+// Positions across underlyings, each symbol's legs held as one strategy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub positions: BTreeMap<String, Strategy>,
+}
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `leg` to the position in `symbol`.
+    pub fn with_leg(mut self, symbol: &str, leg: Leg) -> Self {
+        self.positions.entry(symbol.to_string()).or_default().legs.push(leg);
+        self
+    }
+
+    /// Adds every leg of `strategy` to the position in `symbol`.
+    pub fn with_strategy(mut self, symbol: &str, strategy: Strategy) -> Self {
+        self.positions.entry(symbol.to_string()).or_default().legs.extend(strategy.legs);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.positions.is_empty() {
+            return Err(EngineError::InvalidParams("portfolio must hold at least one position".to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+// Deltas and gammas are in units of each symbol's own underlying, so they only net within
+// a symbol. Vega and theta are in premium currency and add up across the book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioGreeks {
+    pub by_symbol: BTreeMap<String, Greeks>,
+    pub total_vega: f64,
+    pub total_theta: f64,
+}
+
+impl PortfolioGreeks {
+    pub(crate) fn new(by_symbol: BTreeMap<String, Greeks>) -> Self {
+        Self {
+            total_vega: by_symbol.values().map(|greeks| greeks.vega).sum(),
+            total_theta: by_symbol.values().map(|greeks| greeks.theta).sum(),
+            by_symbol,
+        }
+    }
+}
@@ -0,0 +1,1215 @@
+use anyhow::{Result, anyhow};
+use blackscholes::{Greeks as _, Inputs, OptionType, Pricing};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use statrs::function::erf::erfc;
+
+use crate::engine::strike_grid;
+use crate::error::EngineError;
+
+// European options can only be exercised at expiry, American ones at any time before it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptionStyle {
+    #[default]
+    #[serde(alias = "european")]
+    European,
+    #[serde(alias = "american")]
+    American,
+}
+
+// When an option expires: a whole number of days from now, or an absolute UNIX time (seconds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Expiry {
+    Days(u32),
+    Timestamp(i64),
+}
+
+impl Expiry {
+    /// `days` after `now` (UNIX seconds): `Days` for a whole number of days, otherwise the
+    /// `Timestamp` that many days out, to the second.
+    pub fn after(days: DaysToExpiry, now: i64) -> Self {
+        match days.whole_days() {
+            Some(days) => Expiry::Days(days),
+            None => Expiry::Timestamp(now + (days.days() * SECONDS_PER_DAY).round() as i64),
+        }
+    }
+
+    /// Days left until expiry as seen at `now` (UNIX seconds); zero or negative once expired.
+    pub fn days_from(self, now: i64) -> f64 {
+        match self {
+            Expiry::Days(days) => days as f64,
+            Expiry::Timestamp(expiry) => (expiry - now) as f64 / SECONDS_PER_DAY,
+        }
+    }
+}
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Days from now to expiry, fractional for intraday options: 0.25 is six hours out. Whole
+/// day counts convert from `u32`, so `7` works wherever one is taken.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DaysToExpiry(pub f64);
+
+impl DaysToExpiry {
+    pub fn days(self) -> f64 {
+        self.0
+    }
+
+    /// The count as a whole number of days, if it is one.
+    pub fn whole_days(self) -> Option<u32> {
+        (self.0.fract() == 0.0 && self.0 >= 0.0 && self.0 <= u32::MAX as f64).then_some(self.0 as u32)
+    }
+
+    pub(crate) fn validate(self) -> Result<Self> {
+        if !(self.0.is_finite() && self.0 >= 0.0) {
+            return Err(invalid_param("days_to_expiry", "a non-negative number", &self.0));
+        }
+        Ok(self)
+    }
+}
+
+impl From<u32> for DaysToExpiry {
+    fn from(days: u32) -> Self {
+        Self(days as f64)
+    }
+}
+
+impl From<f64> for DaysToExpiry {
+    fn from(days: f64) -> Self {
+        Self(days)
+    }
+}
+
+// Non-finite inputs fail to serialize, as JSON has no way to write them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionParams {
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub underlying_price: f64,
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub strike_price: f64,
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub time_to_expiry: f64, // in days, fractional for intraday expiries
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub volatility: f64,
+    // Continuously compounded; any finite value, including negative rates, which discount by e^{-rT} > 1
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub risk_free_rate: f64,
+    pub is_call: bool,
+    #[serde(default)]
+    pub style: OptionStyle,
+    #[serde(default)]
+    pub day_count: DayCount,
+    #[serde(default)]
+    pub model: PricingModel,
+}
+
+// What the option is written on. Spot prices off the underlying directly; the forward model
+// rolls spot to F = S·e^{(r−q)T} with `funding_rate` as q and prices with Black-76, which is
+// what quarterly-future-settled options need. With zero funding the two agree. Funding may be
+// negative too, which puts the forward above S·e^{rT}.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingModel {
+    #[default]
+    BlackScholesSpot,
+    Black76Forward {
+        #[serde(serialize_with = "crate::finite::serialize")]
+        funding_rate: f64,
+    },
+}
+
+// How many days make a year when turning `time_to_expiry` into the model's T.
+// `TradingDays` reads `time_to_expiry` as trading days over a 252-day year.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayCount {
+    #[default]
+    Act365,
+    Act360,
+    Act36525,
+    TradingDays,
+}
+
+impl DayCount {
+    pub fn days_per_year(self) -> f64 {
+        match self {
+            DayCount::Act365 => 365.0,
+            DayCount::Act360 => 360.0,
+            DayCount::Act36525 => 365.25,
+            DayCount::TradingDays => 252.0,
+        }
+    }
+}
+
+// Black-Scholes implementation
+pub struct BlackScholes;
+
+impl BlackScholes {
+    /// Premium and Greeks for `params` exactly as given, with no oracle or engine involved.
+    /// The premium is checked against its no-arbitrage bounds like the engine's quotes.
+    pub fn price(params: &OptionParams) -> Result<PricedOption> {
+        let premium = Self::calculate_premium(params)?;
+        check_premium_bounds(params, premium)?;
+        Ok(PricedOption { premium, greeks: Self::calculate_greeks(params)? })
+    }
+
+    pub fn calculate_premium(params: &OptionParams) -> Result<f64> {
+        params.validate()?;
+
+        // At or past expiry there is no time value left, and the pricers would divide by zero
+        if params.time_to_expiry <= 0.0 {
+            return Ok(intrinsic_value(params.underlying_price, params.strike_price, params.is_call));
+        }
+
+        // Black-Scholes has no closed form for early exercise
+        if params.style == OptionStyle::American {
+            return Ok(BinomialTree::calculate_premium_american(params, DEFAULT_BINOMIAL_STEPS));
+        }
+
+        // Calculate and return the option price
+        let price: f32 = Self::inputs(params).calc_price().map_err(pricing_error)?;
+        if !price.is_finite() {
+            return Err(anyhow!("Black-Scholes produced a non-finite premium for {:?}", params));
+        }
+        Ok(price as f64) // Convert back to f64 for consistency
+    }
+
+    /// Risk-neutral probability of expiring in the money: N(d2) for calls, N(-d2) for puts.
+    /// Exercise style doesn't change where the underlying ends up, so American options use the same figure.
+    pub fn probability_itm(params: &OptionParams) -> Result<f64> {
+        params.validate()?;
+
+        // With no time or no volatility left the outcome is already decided
+        if params.time_to_expiry <= 0.0 {
+            let in_the_money = intrinsic_value(params.underlying_price, params.strike_price, params.is_call) > 0.0;
+            return Ok(if in_the_money { 1.0 } else { 0.0 });
+        }
+        let inputs = Self::inputs(params);
+        if params.volatility == 0.0 {
+            // The underlying just drifts to s·e^{(r-q)T}
+            let terminal = inputs.s as f64 * ((inputs.r - inputs.q) as f64 * inputs.t as f64).exp();
+            let in_the_money = intrinsic_value(terminal, params.strike_price, params.is_call) > 0.0;
+            return Ok(if in_the_money { 1.0 } else { 0.0 });
+        }
+
+        let (_, d2) = Self::d1_d2(params);
+        let probability = if params.is_call { normal_cdf(d2) } else { normal_cdf(-d2) };
+        if !probability.is_finite() {
+            return Err(anyhow!("Black-Scholes produced a non-finite probability for {:?}", params));
+        }
+        Ok(probability.clamp(0.0, 1.0))
+    }
+
+    /// The intermediate Black-Scholes terms behind a premium, for checking the inputs made it
+    /// through as intended. American options get the same terms with the binomial tree's premium.
+    pub fn calculate_detailed(params: &OptionParams) -> Result<PricingDetails> {
+        params.validate()?;
+        // d1 and d2 divide by σ√T
+        if params.time_to_expiry <= 0.0 {
+            return Err(invalid_param("time_to_expiry", "positive to define d1/d2", &params.time_to_expiry));
+        }
+        if params.volatility == 0.0 {
+            return Err(invalid_param("volatility", "positive to define d1/d2", &params.volatility));
+        }
+
+        let (d1, d2) = Self::d1_d2(params);
+        let premium = Self::calculate_premium(params)?;
+        let delta = Self::calculate_greeks(params)?.delta;
+        Ok(PricingDetails {
+            d1,
+            d2,
+            nd1: normal_cdf(d1),
+            nd2: normal_cdf(d2),
+            discount_factor: (-params.risk_free_rate * params.time_in_years()).exp(),
+            premium,
+            elasticity: elasticity(delta, params.underlying_price, premium),
+        })
+    }
+
+    /// Sensitivities of the premium. American options are bumped and repriced on the binomial tree.
+    pub fn calculate_greeks(params: &OptionParams) -> Result<Greeks> {
+        params.validate()?;
+
+        // At expiry the premium is the payoff: delta is 1 (or -1) in the money, everything else is 0
+        if params.time_to_expiry <= 0.0 {
+            let in_the_money = intrinsic_value(params.underlying_price, params.strike_price, params.is_call) > 0.0;
+            let delta = match (in_the_money, params.is_call) {
+                (false, _) => 0.0,
+                (true, true) => 1.0,
+                (true, false) => -1.0,
+            };
+            return Ok(Greeks { delta, ..Greeks::default() });
+        }
+
+        if params.style == OptionStyle::American {
+            return Ok(Greeks::finite_difference(params));
+        }
+
+        let inputs = Self::inputs(params);
+        let (d1, _) = Self::d1_d2(params);
+        let carry_discount = (-inputs.q as f64 * params.time_in_years()).exp();
+        Ok(Greeks {
+            // e^{-qT} N(d1) for calls, e^{-qT} (N(d1) - 1) for puts
+            delta: carry_discount * if params.is_call { normal_cdf(d1) } else { normal_cdf(d1) - 1.0 },
+            gamma: inputs.calc_gamma().map_err(pricing_error)? as f64,
+            vega: inputs.calc_vega().map_err(pricing_error)? as f64,
+            // The crate's theta is per 1/365.25 of a year; rescale to one day of our convention
+            theta: inputs.calc_theta().map_err(pricing_error)? as f64 * 365.25 / params.day_count.days_per_year(),
+            rho: inputs.calc_rho().map_err(pricing_error)? as f64,
+        })
+    }
+
+    // Black-76 is Black-Scholes on the forward with a dividend yield equal to r,
+    // which zeroes the drift; Greeks are then with respect to the forward
+    fn carry(params: &OptionParams) -> (f64, f64) {
+        match params.model {
+            PricingModel::BlackScholesSpot => (params.underlying_price, 0.0),
+            PricingModel::Black76Forward { .. } => (params.forward_price(), params.risk_free_rate),
+        }
+    }
+
+    // Same terms the crate prices with, in f64
+    fn d1_d2(params: &OptionParams) -> (f64, f64) {
+        let (underlying, dividend_yield) = Self::carry(params);
+        let t = params.time_in_years();
+        let sigma_sqrt_t = params.volatility * t.sqrt();
+        let d1 = ((underlying / params.strike_price).ln()
+            + (params.risk_free_rate - dividend_yield + params.volatility * params.volatility / 2.0) * t)
+            / sigma_sqrt_t;
+        (d1, d1 - sigma_sqrt_t)
+    }
+
+    fn inputs(params: &OptionParams) -> Inputs {
+        // Convert time_to_expiry from days to years under the params' day-count convention
+        let time_in_years = params.time_in_years() as f32;
+
+        // Determine option type
+        let option_type = if params.is_call {
+            OptionType::Call
+        } else {
+            OptionType::Put
+        };
+
+        let (underlying, dividend_yield) = Self::carry(params);
+
+        // Create inputs for black-scholes calculation
+        Inputs::new(
+            option_type,                     // Call or Put
+            underlying as f32,               // Current price (S) or forward (F)
+            params.strike_price as f32,      // Strike price (K)
+            None,                            // Premium (not used for pricing)
+            params.risk_free_rate as f32,    // Risk-free rate
+            dividend_yield as f32,           // Dividend yield (typically 0 for crypto spot)
+            time_in_years,                   // Time to maturity in years
+            Some(params.volatility as f32),  // Volatility
+        )
+    }
+
+    /// Premiums for `params` repriced at each of `strikes`, in the same order.
+    pub fn calculate_premiums(params: &OptionParams, strikes: &[f64]) -> Result<Vec<f64>> {
+        strikes.iter()
+            .map(|&strike| Self::calculate_premium(&params.with_strike(strike)))
+            .collect()
+    }
+
+    /// Same as `calculate_premiums`, with the strikes spread across the rayon thread pool.
+    #[cfg(feature = "rayon")]
+    pub fn calculate_premiums_par(params: &OptionParams, strikes: &[f64]) -> Result<Vec<f64>> {
+        use rayon::prelude::*;
+
+        // Indexed parallel iterators collect in input order
+        strikes.par_iter()
+            .map(|&strike| Self::calculate_premium(&params.with_strike(strike)))
+            .collect()
+    }
+}
+
+/// What [`BlackScholes::price`] returns.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PricedOption {
+    #[serde(serialize_with = "crate::finite::serialize")]
+    pub premium: f64,
+    pub greeks: Greeks,
+}
+
+/// Intermediate Black-Scholes terms, see [`BlackScholes::calculate_detailed`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PricingDetails {
+    pub d1: f64,
+    pub d2: f64,
+    pub nd1: f64,
+    pub nd2: f64,
+    // e^{-rT}
+    pub discount_factor: f64,
+    pub premium: f64,
+    // Δ·S / premium, see `elasticity`
+    pub elasticity: f64,
+}
+
+// Premiums below this count as zero for `elasticity`
+const ELASTICITY_MIN_PREMIUM: f64 = 1e-12;
+
+/// Lambda, the option's leverage: percent change in premium per percent move in the
+/// underlying, Δ·S / premium. A worthless (deep OTM) option has none to divide by and gets
+/// an infinite elasticity, signed like its delta.
+pub fn elasticity(delta: f64, underlying_price: f64, premium: f64) -> f64 {
+    if premium.abs() < ELASTICITY_MIN_PREMIUM {
+        return f64::INFINITY.copysign(delta);
+    }
+    delta * underlying_price / premium
+}
+
+/// Payoff if exercised right now: max(S - K, 0) for calls, max(K - S, 0) for puts.
+pub fn intrinsic_value(underlying_price: f64, strike_price: f64, is_call: bool) -> f64 {
+    if is_call {
+        (underlying_price - strike_price).max(0.0)
+    } else {
+        (strike_price - underlying_price).max(0.0)
+    }
+}
+
+/// Underlying price at expiry where a long position exactly recovers its premium:
+/// K + premium for calls, K - premium for puts.
+pub fn breakeven(strike_price: f64, premium: f64, is_call: bool) -> f64 {
+    if is_call {
+        strike_price + premium
+    } else {
+        strike_price - premium
+    }
+}
+
+/// Where a strike sits relative to the underlying, for one option type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Moneyness {
+    Itm,
+    Atm,
+    Otm,
+}
+
+/// One row of an options chain's moneyness column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrikeMoneyness {
+    pub strike: f64,
+    // S / K: above 1 calls are in the money, below 1 puts are
+    pub moneyness: f64,
+    pub call: Moneyness,
+    pub put: Moneyness,
+}
+
+/// Labels each strike ATM when S/K is within `atm_band` of 1 (inclusive, so a strike exactly
+/// at the underlying is always ATM), otherwise ITM or OTM for calls and the opposite for puts.
+pub fn classify_strikes(underlying_price: f64, strikes: &[f64], atm_band: f64) -> Vec<StrikeMoneyness> {
+    strikes.iter()
+        .map(|&strike| {
+            let moneyness = underlying_price / strike;
+            let (call, put) = if (moneyness - 1.0).abs() <= atm_band {
+                (Moneyness::Atm, Moneyness::Atm)
+            } else if moneyness > 1.0 {
+                (Moneyness::Itm, Moneyness::Otm)
+            } else {
+                (Moneyness::Otm, Moneyness::Itm)
+            };
+            StrikeMoneyness { strike, moneyness, call, put }
+        })
+        .collect()
+}
+
+/// Profit or loss at expiry per unit of one option bought (`long`) or sold at `premium`,
+/// if the underlying finishes at `terminal_price`: payoff minus premium, negated for shorts.
+pub fn simulate_expiry(strike_price: f64, premium: f64, is_call: bool, long: bool, terminal_price: f64) -> f64 {
+    let pnl = intrinsic_value(terminal_price, strike_price, is_call) - premium;
+    if long { pnl } else { -pnl }
+}
+
+/// Units of the underlying to trade to make a position delta-neutral: positive to buy,
+/// negative to sell. `position_contracts` is signed (negative for a short position).
+/// Strategy Greeks from `calculate_strategy_greeks` are already netted over the legs, so
+/// pass them with the number of strategies held.
+pub fn hedge_quantity(greeks: &Greeks, position_contracts: f64, contract_multiplier: f64) -> f64 {
+    -greeks.delta * position_contracts * contract_multiplier
+}
+
+/// `simulate_expiry` over terminal prices from min to max (inclusive) in `step` increments,
+/// as `(terminal_price, pnl)` points for plotting.
+pub fn payoff_curve(
+    strike_price: f64,
+    premium: f64,
+    is_call: bool,
+    long: bool,
+    price_range: (f64, f64, f64), // (min, max, step)
+) -> Vec<(f64, f64)> {
+    strike_grid(price_range)
+        .into_iter()
+        .map(|terminal| (terminal, simulate_expiry(strike_price, premium, is_call, long, terminal)))
+        .collect()
+}
+
+/// Premium `day` days from now for each day up to `days_ahead`, with spot, vol and rates held
+/// at `params`: `(0, today's premium)`, `(1, tomorrow's)`, and so on. Stops early at expiry,
+/// whose point is the intrinsic value.
+pub fn project_theta(params: &OptionParams, days_ahead: u32) -> Result<Vec<(u32, f64)>> {
+    let mut projection = Vec::with_capacity(days_ahead as usize + 1);
+    for day in 0..=days_ahead {
+        let remaining = (params.time_to_expiry - day as f64).max(0.0);
+        let premium = BlackScholes::calculate_premium(&OptionParams { time_to_expiry: remaining, ..params.clone() })?;
+        projection.push((day, premium));
+        if remaining == 0.0 {
+            break;
+        }
+    }
+    Ok(projection)
+}
+
+// Standard normal CDF
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+// f32 pricing is only good to about seven significant figures
+const BOUND_TOLERANCE: f64 = 1e-5;
+
+/// Checks `premium` against the no-arbitrage bounds: a call is worth at most the underlying
+/// (S·e^{-qT} under the forward model), a put at most the discounted strike K·e^{-rT}, and
+/// neither less than zero. American options may also be worth exercising today, so their
+/// bounds are never below S or K.
+pub fn check_premium_bounds(params: &OptionParams, premium: f64) -> Result<(), EngineError> {
+    let t = params.time_in_years().max(0.0);
+    let (kind, european, immediate) = if params.is_call {
+        let (underlying, dividend_yield) = BlackScholes::carry(params);
+        // Exercising an American option on the forward delivers the forward
+        ("call", underlying * (-dividend_yield * t).exp(), params.forward_price().max(params.underlying_price))
+    } else {
+        ("put", params.strike_price * (-params.risk_free_rate * t).exp(), params.strike_price)
+    };
+    let bound = match params.style {
+        OptionStyle::European => european,
+        OptionStyle::American => european.max(immediate),
+    };
+    let slack = bound * BOUND_TOLERANCE;
+    if premium.is_finite() && premium >= -slack && premium <= bound + slack {
+        return Ok(());
+    }
+    Err(EngineError::PricingFailed {
+        kind,
+        premium,
+        bound,
+        underlying: params.underlying_price,
+        strike: params.strike_price,
+    })
+}
+
+// The blackscholes crate reports failures as bare strings
+fn pricing_error(message: String) -> anyhow::Error {
+    anyhow!("Black-Scholes pricing failed: {}", message)
+}
+
+pub(crate) fn invalid_param(field: &str, rule: &str, value: &dyn std::fmt::Display) -> anyhow::Error {
+    EngineError::InvalidParams(format!("{} must be {}, got {}", field, rule, value)).into()
+}
+
+impl OptionParams {
+    pub fn time_in_years(&self) -> f64 {
+        self.time_to_expiry / self.day_count.days_per_year()
+    }
+
+    /// The forward the option settles against; plain spot under `BlackScholesSpot`.
+    pub fn forward_price(&self) -> f64 {
+        match self.model {
+            PricingModel::BlackScholesSpot => self.underlying_price,
+            PricingModel::Black76Forward { funding_rate } => {
+                self.underlying_price * ((self.risk_free_rate - funding_rate) * self.time_in_years()).exp()
+            }
+        }
+    }
+
+    /// Rejects inputs the pricers can't handle, naming the offending field.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |field, rule, value: &dyn std::fmt::Display| Err(invalid_param(field, rule, value));
+
+        if !(self.underlying_price.is_finite() && self.underlying_price > 0.0) {
+            return invalid("underlying_price", "a positive number", &self.underlying_price);
+        }
+        if !(self.strike_price.is_finite() && self.strike_price > 0.0) {
+            return invalid("strike_price", "a positive number", &self.strike_price);
+        }
+        // Zero or negative time is allowed and means the option has expired
+        if !self.time_to_expiry.is_finite() {
+            return invalid("time_to_expiry", "finite", &self.time_to_expiry);
+        }
+        if !(self.volatility.is_finite() && self.volatility >= 0.0) {
+            return invalid("volatility", "a non-negative number", &self.volatility);
+        }
+        if !self.risk_free_rate.is_finite() {
+            return invalid("risk_free_rate", "finite", &self.risk_free_rate);
+        }
+        if let PricingModel::Black76Forward { funding_rate } = self.model
+            && !funding_rate.is_finite()
+        {
+            return invalid("funding_rate", "finite", &funding_rate);
+        }
+        Ok(())
+    }
+
+    fn with_strike(&self, strike_price: f64) -> Self {
+        Self { strike_price, ..self.clone() }
+    }
+}
+
+// Vega and rho are per 1 percentage point, theta is per calendar day
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+impl Greeks {
+    /// Every sensitivity multiplied by `factor`, e.g. a signed position size.
+    pub fn scaled(self, factor: f64) -> Self {
+        Self {
+            delta: self.delta * factor,
+            gamma: self.gamma * factor,
+            vega: self.vega * factor,
+            theta: self.theta * factor,
+            rho: self.rho * factor,
+        }
+    }
+
+    // Central differences for spot, vol and rate; theta steps one day closer to expiry
+    fn finite_difference(params: &OptionParams) -> Self {
+        let price = |p: &OptionParams| BinomialTree::calculate_premium_american(p, DEFAULT_BINOMIAL_STEPS);
+        let bumped = |f: &dyn Fn(&mut OptionParams)| {
+            let mut p = params.clone();
+            f(&mut p);
+            price(&p)
+        };
+
+        let ds = params.underlying_price * 0.01;
+        let up = bumped(&|p| p.underlying_price += ds);
+        let mid = price(params);
+        let down = bumped(&|p| p.underlying_price -= ds);
+
+        let theta = if params.time_to_expiry > 1.0 {
+            bumped(&|p| p.time_to_expiry -= 1.0) - mid
+        } else {
+            0.0
+        };
+
+        Self {
+            delta: (up - down) / (2.0 * ds),
+            gamma: (up - 2.0 * mid + down) / (ds * ds),
+            vega: (bumped(&|p| p.volatility += 0.01) - bumped(&|p| p.volatility -= 0.01)) / 2.0,
+            theta,
+            rho: (bumped(&|p| p.risk_free_rate += 0.01) - bumped(&|p| p.risk_free_rate -= 0.01)) / 2.0,
+        }
+    }
+}
+
+impl std::ops::Add for Greeks {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            delta: self.delta + other.delta,
+            gamma: self.gamma + other.gamma,
+            vega: self.vega + other.vega,
+            theta: self.theta + other.theta,
+            rho: self.rho + other.rho,
+        }
+    }
+}
+
+impl std::iter::Sum for Greeks {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, greeks| total + greeks)
+    }
+}
+
+pub const DEFAULT_BINOMIAL_STEPS: usize = 500;
+
+// Cox-Ross-Rubinstein binomial tree
+pub struct BinomialTree;
+
+impl BinomialTree {
+    pub fn calculate_premium_american(params: &OptionParams, steps: usize) -> f64 {
+        let steps = steps.max(1);
+        let time_in_years = params.time_in_years();
+        let dt = time_in_years / steps as f64;
+
+        // A forward has no drift under the risk-neutral measure; exercise pays F - K
+        let (underlying, drift) = match params.model {
+            PricingModel::BlackScholesSpot => (params.underlying_price, params.risk_free_rate),
+            PricingModel::Black76Forward { .. } => (params.forward_price(), 0.0),
+        };
+
+        // Up/down factors and risk-neutral up probability for one step
+        let up = (params.volatility * dt.sqrt()).exp();
+        let down = 1.0 / up;
+        let growth = (drift * dt).exp();
+        // Carry so large relative to vol that growth falls outside [down, up] would make this
+        // a "probability" outside [0, 1]; pin it so the tree stays an average of payoffs
+        let p_up = ((growth - down) / (up - down)).clamp(0.0, 1.0);
+        let discount = (-params.risk_free_rate * dt).exp();
+
+        let intrinsic = |spot: f64| {
+            if params.is_call {
+                (spot - params.strike_price).max(0.0)
+            } else {
+                (params.strike_price - spot).max(0.0)
+            }
+        };
+
+        // Payoffs at expiry, node i has i down moves
+        let mut values: Vec<f64> = (0..=steps)
+            .map(|i| intrinsic(underlying * up.powi((steps - i) as i32) * down.powi(i as i32)))
+            .collect();
+
+        // Walk back to today, exercising early wherever that beats holding
+        for step in (0..steps).rev() {
+            for i in 0..=step {
+                let spot = underlying * up.powi((step - i) as i32) * down.powi(i as i32);
+                let hold = discount * (p_up * values[i] + (1.0 - p_up) * values[i + 1]);
+                values[i] = hold.max(intrinsic(spot));
+            }
+        }
+
+        values[0]
+    }
+}
+
+/// A Monte Carlo premium with the standard error of the estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MonteCarloEstimate {
+    pub premium: f64,
+    pub std_error: f64,
+}
+
+// Simulates GBM terminal prices independently of the closed form, to cross-check it
+pub struct MonteCarlo;
+
+impl MonteCarlo {
+    /// Discounted mean payoff over `paths` simulated terminal prices; the same `seed` always
+    /// gives the same price. European exercise only.
+    pub fn calculate_premium_mc(params: &OptionParams, paths: usize, seed: u64) -> Result<f64> {
+        Ok(Self::estimate(params, paths, seed)?.premium)
+    }
+
+    pub fn estimate(params: &OptionParams, paths: usize, seed: u64) -> Result<MonteCarloEstimate> {
+        params.validate()?;
+        if params.style == OptionStyle::American {
+            return Err(invalid_param("style", "european for Monte Carlo pricing", &"american"));
+        }
+        if paths == 0 {
+            return Err(invalid_param("paths", "at least 1", &paths));
+        }
+        if params.time_to_expiry <= 0.0 {
+            let premium = intrinsic_value(params.underlying_price, params.strike_price, params.is_call);
+            return Ok(MonteCarloEstimate { premium, std_error: 0.0 });
+        }
+
+        // Same carry as the closed form: S with q = 0 on spot, F with q = r on the forward
+        let (underlying, dividend_yield) = BlackScholes::carry(params);
+        let t = params.time_in_years();
+        let drift = (params.risk_free_rate - dividend_yield - params.volatility * params.volatility / 2.0) * t;
+        let diffusion = params.volatility * t.sqrt();
+        let discount = (-params.risk_free_rate * t).exp();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        for _ in 0..paths {
+            let terminal = underlying * (drift + diffusion * standard_normal(&mut rng)).exp();
+            let payoff = discount * intrinsic_value(terminal, params.strike_price, params.is_call);
+            sum += payoff;
+            sum_sq += payoff * payoff;
+        }
+
+        let n = paths as f64;
+        let mean = sum / n;
+        let variance = (sum_sq / n - mean * mean).max(0.0) * n / (n - 1.0).max(1.0);
+        Ok(MonteCarloEstimate { premium: mean, std_error: (variance / n).sqrt() })
+    }
+}
+
+// Box-Muller; 1 - u keeps the log argument in (0, 1]
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1 = 1.0 - rng.random::<f64>();
+    let u2 = rng.random::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_black_scholes_call() {
+        let params = OptionParams {
+            underlying_price: 100.0,
+            strike_price: 100.0,
+            time_to_expiry: 1111.0,
+            volatility: 0.2,
+            risk_free_rate: 0.05,
+            is_call: true,
+            style: OptionStyle::European,
+            day_count: DayCount::default(),
+            model: PricingModel::default(),
+        };
+
+        let premium = BlackScholes::calculate_premium(&params).unwrap();
+        assert!(premium > 0.0);
+        assert!(premium < 50.0); // ATM call should be reasonable
+    }
+
+    #[test]
+    fn test_black_scholes_reference_premiums() {
+        // Closed-form values worked out in f64 to 1e-12; the crate prices in f32, so tolerances
+        // are a few parts per million of the underlying. (S, K, days, vol, r, call, premium)
+        let cases = [
+            (100.0, 100.0, 365.0, 0.2, 0.05, true, 10.450583572185565), // the textbook ATM call
+            (100.0, 100.0, 365.0, 0.2, 0.05, false, 5.573526022256971),
+            (100.0, 90.0, 182.5, 0.25, 0.03, true, 13.790848961769484), // ITM call
+            (100.0, 110.0, 182.5, 0.25, 0.03, false, 12.260864539521961), // ITM put
+            (100.0, 120.0, 91.25, 0.3, 0.05, true, 1.0491632166412934), // OTM call
+            (100.0, 80.0, 91.25, 0.3, 0.05, false, 0.3310504749797447), // OTM put
+            (2.0, 2.1, 1.0, 0.8, 0.05, true, 0.005201911883586319), // one day to expiry
+            (2.0, 2.0, 7.0, 0.8, 0.05, false, 0.08735333859509886),
+            (60000.0, 65000.0, 30.0, 0.5, 0.04, true, 1669.7200452432517),
+        ];
+        for (underlying, strike, days, volatility, rate, is_call, expected) in cases {
+            let params = OptionParams {
+                time_to_expiry: days,
+                volatility,
+                risk_free_rate: rate,
+                ..params(underlying, strike, is_call, OptionStyle::European)
+            };
+            let premium = BlackScholes::calculate_premium(&params).unwrap();
+            let tolerance = 5e-6 * underlying;
+            assert!(
+                (premium - expected).abs() < tolerance,
+                "S={} K={} days={} call={}: {} vs {}", underlying, strike, days, is_call, premium, expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_black_scholes_put() {
+        let params = OptionParams {
+            underlying_price: 100.0,
+            strike_price: 100.0,
+            time_to_expiry: 1111.0,
+            volatility: 0.2,
+            risk_free_rate: 0.05,
+            is_call: false,
+            style: OptionStyle::European,
+            day_count: DayCount::default(),
+            model: PricingModel::default(),
+        };
+
+        let premium = BlackScholes::calculate_premium(&params).unwrap();
+        assert!(premium > 0.0);
+        let call = BlackScholes::calculate_premium(&OptionParams { is_call: true, ..params.clone() }).unwrap();
+        // Put-call parity: C - P = S - K·e^{-rT}
+        let forward_gap = params.underlying_price - params.strike_price * (-params.risk_free_rate * params.time_in_years()).exp();
+        assert!((call - premium - forward_gap).abs() < 1e-3, "call {} put {} gap {}", call, premium, forward_gap);
+        assert!(premium < 50.0);
+    }
+
+    fn params(underlying_price: f64, strike_price: f64, is_call: bool, style: OptionStyle) -> OptionParams {
+        OptionParams {
+            underlying_price,
+            strike_price,
+            time_to_expiry: 365.0,
+            volatility: 0.3,
+            risk_free_rate: 0.05,
+            is_call,
+            style,
+            day_count: DayCount::default(),
+            model: PricingModel::default(),
+        }
+    }
+
+    #[test]
+    fn test_theta_projection_accelerates_into_expiry() {
+        let atm = OptionParams { time_to_expiry: 30.0, ..params(100.0, 100.0, true, OptionStyle::European) };
+        let projection = project_theta(&atm, 40).unwrap();
+
+        // 30 days of decay plus the expiry point; nothing past expiry
+        assert_eq!(projection.len(), 31);
+        assert_eq!(projection[0].1, BlackScholes::calculate_premium(&atm).unwrap());
+        assert_eq!(projection[30], (30, 0.0));
+        assert!(projection.windows(2).all(|pair| pair[1].1 < pair[0].1));
+
+        // The last week burns faster per day than the first
+        let first_week = (projection[0].1 - projection[7].1) / 7.0;
+        let last_week = (projection[23].1 - projection[30].1) / 7.0;
+        assert!(last_week > first_week, "{} vs {}", last_week, first_week);
+    }
+
+    #[test]
+    fn test_hedge_quantity_offsets_delta() {
+        let call = BlackScholes::calculate_greeks(&params(100.0, 100.0, true, OptionStyle::European)).unwrap();
+        // Long 10 calls of 100 units each: sell delta * 1000 of the underlying
+        let hedge = hedge_quantity(&call, 10.0, 100.0);
+        assert!((hedge + call.delta * 1000.0).abs() < 1e-9);
+        assert!(hedge < 0.0);
+
+        // Short puts have positive delta exposure too, hedged by selling
+        let put = BlackScholes::calculate_greeks(&params(100.0, 100.0, false, OptionStyle::European)).unwrap();
+        assert!(hedge_quantity(&put, -5.0, 1.0) < 0.0);
+        assert_eq!(hedge_quantity(&Greeks::default(), 10.0, 100.0), 0.0);
+    }
+
+    #[test]
+    fn test_option_params_json_roundtrip() {
+        let original = OptionParams {
+            time_to_expiry: 0.1 + 0.2, // not exactly representable in decimal
+            day_count: DayCount::TradingDays,
+            model: PricingModel::Black76Forward { funding_rate: -0.013 },
+            ..params(2.0, 1.0 / 3.0, false, OptionStyle::American)
+        };
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(serde_json::from_str::<OptionParams>(&json).unwrap(), original);
+
+        let broken = OptionParams { volatility: f64::NAN, ..original };
+        let err = serde_json::to_string(&broken).unwrap_err();
+        assert!(err.to_string().contains("non-finite"), "{}", err);
+        // What a NaN-tolerant writer would have produced is rejected on the way back in too
+        assert!(serde_json::from_str::<OptionParams>(&json.replace("0.30000000000000004", "null")).is_err());
+    }
+
+    #[test]
+    fn test_american_put_worth_at_least_european() {
+        for strike in [80.0, 100.0, 120.0] {
+            let european = BlackScholes::calculate_premium(&params(100.0, strike, false, OptionStyle::European)).unwrap();
+            let american = BlackScholes::calculate_premium(&params(100.0, strike, false, OptionStyle::American)).unwrap();
+            assert!(american >= european, "K={}: american {} < european {}", strike, american, european);
+        }
+
+        // Deep ITM the early-exercise premium is clearly positive
+        let european = BlackScholes::calculate_premium(&params(100.0, 140.0, false, OptionStyle::European)).unwrap();
+        let american = BlackScholes::calculate_premium(&params(100.0, 140.0, false, OptionStyle::American)).unwrap();
+        assert!(american - european > 0.5);
+    }
+
+    #[test]
+    fn test_american_call_matches_european_without_dividends() {
+        // Never optimal to exercise a call early on a non-paying underlying
+        let european = BlackScholes::calculate_premium(&params(100.0, 100.0, true, OptionStyle::European)).unwrap();
+        let american = BinomialTree::calculate_premium_american(&params(100.0, 100.0, true, OptionStyle::American), 1000);
+        assert!((american - european).abs() < 0.02, "american {} european {}", american, european);
+    }
+
+    #[test]
+    fn test_greeks_signs() {
+        let call = BlackScholes::calculate_greeks(&params(100.0, 100.0, true, OptionStyle::European)).unwrap();
+        let put = BlackScholes::calculate_greeks(&params(100.0, 100.0, false, OptionStyle::European)).unwrap();
+        assert!(call.delta > 0.0 && call.delta < 1.0);
+        assert!(put.delta < 0.0 && put.delta > -1.0);
+        // Put-call parity: call delta - put delta = 1 without dividends
+        assert!((call.delta - put.delta - 1.0).abs() < 1e-4);
+        assert!((call.gamma - put.gamma).abs() < 1e-5);
+        assert!(call.vega > 0.0 && call.theta < 0.0);
+    }
+
+    #[test]
+    fn test_american_greeks_close_to_european_for_calls() {
+        let european = BlackScholes::calculate_greeks(&params(100.0, 100.0, true, OptionStyle::European)).unwrap();
+        let american = BlackScholes::calculate_greeks(&params(100.0, 100.0, true, OptionStyle::American)).unwrap();
+        assert!((american.delta - european.delta).abs() < 0.01, "{:?} vs {:?}", american, european);
+        assert!((american.vega - european.vega).abs() < 0.01, "{:?} vs {:?}", american, european);
+    }
+
+    #[test]
+    fn test_calculate_premiums_matches_single_pricing() {
+        let base = params(100.0, 100.0, false, OptionStyle::European);
+        let strikes = [90.0, 100.0, 110.0];
+        let premiums = BlackScholes::calculate_premiums(&base, &strikes).unwrap();
+        for (strike, premium) in strikes.iter().zip(&premiums) {
+            assert_eq!(*premium, BlackScholes::calculate_premium(&params(100.0, *strike, false, OptionStyle::European)).unwrap());
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_premiums_match_serial() {
+        let strikes: Vec<f64> = (0..1000).map(|i| 50.0 + i as f64 * 0.1).collect();
+        for is_call in [true, false] {
+            let base = params(100.0, 100.0, is_call, OptionStyle::European);
+            assert_eq!(
+                BlackScholes::calculate_premiums_par(&base, &strikes).unwrap(),
+                BlackScholes::calculate_premiums(&base, &strikes).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_params() {
+        let good = params(100.0, 100.0, true, OptionStyle::European);
+        assert!(good.validate().is_ok());
+
+        type Corrupt = fn(&mut OptionParams);
+        let cases: [(&str, Corrupt); 9] = [
+            ("underlying_price", |p| p.underlying_price = 0.0),
+            ("underlying_price", |p| p.underlying_price = -5.0),
+            ("strike_price", |p| p.strike_price = -100.0),
+            ("strike_price", |p| p.strike_price = f64::NAN),
+            ("time_to_expiry", |p| p.time_to_expiry = f64::NAN),
+            ("time_to_expiry", |p| p.time_to_expiry = f64::INFINITY),
+            ("volatility", |p| p.volatility = f64::NAN),
+            ("volatility", |p| p.volatility = -0.1),
+            ("risk_free_rate", |p| p.risk_free_rate = f64::INFINITY),
+        ];
+        for (field, corrupt) in cases {
+            let mut bad = good.clone();
+            corrupt(&mut bad);
+            let err = BlackScholes::calculate_premium(&bad).unwrap_err();
+            assert!(matches!(err.downcast_ref::<EngineError>(), Some(EngineError::InvalidParams(_))), "{:#}", err);
+            assert!(err.to_string().contains(field), "{} should name {}", err, field);
+            assert!(BlackScholes::calculate_greeks(&bad).is_err());
+        }
+    }
+
+    #[test]
+    fn test_expiry_days_from() {
+        assert_eq!(Expiry::Days(7).days_from(1_000), 7.0);
+        assert_eq!(Expiry::Timestamp(1_000 + 6 * 3600).days_from(1_000), 0.25);
+        assert!(Expiry::Timestamp(500).days_from(1_000) < 0.0);
+    }
+
+    #[test]
+    fn test_zero_time_returns_intrinsic_value() {
+        let at_expiry = |strike, is_call, style| OptionParams {
+            time_to_expiry: 0.0,
+            ..params(100.0, strike, is_call, style)
+        };
+        for style in [OptionStyle::European, OptionStyle::American] {
+            // ATM, ITM and OTM calls and puts
+            assert_eq!(BlackScholes::calculate_premium(&at_expiry(100.0, true, style)).unwrap(), 0.0);
+            assert_eq!(BlackScholes::calculate_premium(&at_expiry(100.0, false, style)).unwrap(), 0.0);
+            assert_eq!(BlackScholes::calculate_premium(&at_expiry(90.0, true, style)).unwrap(), 10.0);
+            assert_eq!(BlackScholes::calculate_premium(&at_expiry(110.0, false, style)).unwrap(), 10.0);
+            assert_eq!(BlackScholes::calculate_premium(&at_expiry(110.0, true, style)).unwrap(), 0.0);
+            assert_eq!(BlackScholes::calculate_premium(&at_expiry(90.0, false, style)).unwrap(), 0.0);
+        }
+
+        let expired = OptionParams { time_to_expiry: -2.0, ..params(100.0, 90.0, true, OptionStyle::European) };
+        assert_eq!(BlackScholes::calculate_premium(&expired).unwrap(), 10.0);
+        assert_eq!(BlackScholes::calculate_greeks(&expired).unwrap().delta, 1.0);
+        assert_eq!(BlackScholes::calculate_greeks(&at_expiry(110.0, true, OptionStyle::European)).unwrap(), Greeks::default());
+    }
+
+    #[test]
+    fn test_probability_itm() {
+        let prob = |strike, is_call| BlackScholes::probability_itm(&params(100.0, strike, is_call, OptionStyle::European)).unwrap();
+
+        // N(d2) with d2 = (0.05 - 0.045) / 0.3 at the money
+        assert!((prob(100.0, true) - 0.5066).abs() < 1e-3, "{}", prob(100.0, true));
+        for strike in [80.0, 100.0, 120.0] {
+            assert!((prob(strike, true) + prob(strike, false) - 1.0).abs() < 1e-5);
+        }
+        assert!(prob(80.0, true) > prob(100.0, true) && prob(100.0, true) > prob(120.0, true));
+
+        // More time gives an out-of-the-money call more chance to finish in the money
+        let otm_call = |time_to_expiry| OptionParams { time_to_expiry, ..params(100.0, 120.0, true, OptionStyle::European) };
+        let short = BlackScholes::probability_itm(&otm_call(30.0)).unwrap();
+        let long = BlackScholes::probability_itm(&otm_call(365.0)).unwrap();
+        assert!(short < long, "{} vs {}", short, long);
+        assert_eq!(BlackScholes::probability_itm(&otm_call(0.0)).unwrap(), 0.0);
+
+        let no_vol = OptionParams { volatility: 0.0, ..params(100.0, 100.0, true, OptionStyle::European) };
+        assert_eq!(BlackScholes::probability_itm(&no_vol).unwrap(), 1.0); // spot drifts above the strike at r
+    }
+
+    #[test]
+    fn test_calculate_detailed_exposes_intermediate_terms() {
+        let details = BlackScholes::calculate_detailed(&params(100.0, 100.0, true, OptionStyle::European)).unwrap();
+
+        // d1 = (r + σ²/2)T / σ√T at the money
+        assert!((details.d1 - 0.316667).abs() < 1e-6, "{:?}", details);
+        assert!((details.d2 - 0.016667).abs() < 1e-6);
+        assert!((details.nd1 - 0.624252).abs() < 1e-5);
+        assert!((details.nd2 - 0.506649).abs() < 1e-5);
+        assert!((details.discount_factor - (-0.05_f64).exp()).abs() < 1e-12);
+
+        // The premium is S·N(d1) - K·e^{-rT}·N(d2), as priced
+        let closed_form = 100.0 * details.nd1 - 100.0 * details.discount_factor * details.nd2;
+        assert!((details.premium - closed_form).abs() < 1e-3, "{} vs {}", details.premium, closed_form);
+        let delta = BlackScholes::calculate_greeks(&params(100.0, 100.0, true, OptionStyle::European)).unwrap().delta;
+        assert!((details.elasticity - delta * 100.0 / details.premium).abs() < 1e-12);
+        // An ATM call levers the underlying several times over; a put's elasticity is negative
+        assert!(details.elasticity > 3.0 && details.elasticity < 6.0, "{}", details.elasticity);
+        assert!(elasticity(-0.4, 100.0, 8.0) < 0.0);
+        assert_eq!(elasticity(1e-30, 100.0, 0.0), f64::INFINITY);
+        assert_eq!(elasticity(-1e-30, 100.0, 0.0), f64::NEG_INFINITY);
+
+        let expired = OptionParams { time_to_expiry: 0.0, ..params(100.0, 100.0, true, OptionStyle::European) };
+        let err = BlackScholes::calculate_detailed(&expired).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(EngineError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn test_negative_rates_keep_premiums_positive_and_parity() {
+        let negative = |strike, is_call, style| OptionParams { risk_free_rate: -0.02, ..params(100.0, strike, is_call, style) };
+        let premium = |strike, is_call, style| BlackScholes::calculate_premium(&negative(strike, is_call, style)).unwrap();
+
+        for strike in [80.0, 100.0, 120.0] {
+            let call = premium(strike, true, OptionStyle::European);
+            let put = premium(strike, false, OptionStyle::European);
+            assert!(call > 0.0 && put > 0.0, "K={}: {} / {}", strike, call, put);
+
+            // C - P = S - K·e^{-rT}, where e^{-rT} > 1 now
+            let parity = 100.0 - strike * 0.02_f64.exp();
+            assert!((call - put - parity).abs() < 1e-3, "K={}: {} vs {}", strike, call - put, parity);
+
+            for is_call in [true, false] {
+                let american = premium(strike, is_call, OptionStyle::American);
+                let european = premium(strike, is_call, OptionStyle::European);
+                assert!(american.is_finite() && american >= european - 1e-2, "K={}: {} < {}", strike, american, european);
+            }
+
+            let prob = BlackScholes::probability_itm(&negative(strike, true, OptionStyle::European)).unwrap();
+            assert!((0.0..=1.0).contains(&prob));
+        }
+
+        // Negative funding on the forward model: Black-76 parity C - P = e^{-rT}(F - K)
+        let forward = |is_call| OptionParams {
+            model: PricingModel::Black76Forward { funding_rate: -0.03 },
+            ..negative(105.0, is_call, OptionStyle::European)
+        };
+        let f = forward(true).forward_price();
+        assert!(f > 100.0);
+        let call = BlackScholes::calculate_premium(&forward(true)).unwrap();
+        let put = BlackScholes::calculate_premium(&forward(false)).unwrap();
+        assert!(call > 0.0 && put > 0.0);
+        let parity = 0.02_f64.exp() * (f - 105.0);
+        assert!((call - put - parity).abs() < 1e-3, "{} vs {}", call - put, parity);
+    }
+
+    #[test]
+    fn test_simulate_expiry_payoffs() {
+        // Long call: loses the premium below the strike, breaks even at K + premium
+        assert_eq!(simulate_expiry(100.0, 5.0, true, true, 90.0), -5.0);
+        assert_eq!(simulate_expiry(100.0, 5.0, true, true, breakeven(100.0, 5.0, true)), 0.0);
+        assert_eq!(simulate_expiry(100.0, 5.0, true, true, 120.0), 15.0);
+        // Long put and the short side mirror it
+        assert_eq!(simulate_expiry(100.0, 5.0, false, true, 80.0), 15.0);
+        assert_eq!(simulate_expiry(100.0, 5.0, false, false, 80.0), -15.0);
+        assert_eq!(simulate_expiry(100.0, 5.0, true, false, 90.0), 5.0);
+
+        let curve = payoff_curve(100.0, 5.0, true, true, (90.0, 110.0, 10.0));
+        assert_eq!(curve, vec![(90.0, -5.0), (100.0, -5.0), (110.0, 5.0)]);
+    }
+
+    #[test]
+    fn test_monte_carlo_converges_to_closed_form() {
+        for (strike, is_call) in [(100.0, true), (110.0, true), (90.0, false)] {
+            let option = params(100.0, strike, is_call, OptionStyle::European);
+            let closed_form = BlackScholes::calculate_premium(&option).unwrap();
+            let estimate = MonteCarlo::estimate(&option, 200_000, 42).unwrap();
+            let error = (estimate.premium - closed_form).abs();
+            assert!(error < 4.0 * estimate.std_error, "K={}: mc {:?} vs {}", strike, estimate, closed_form);
+        }
+
+        // Fixed seed, fixed answer; the forward model goes through the same carry
+        let option = params(100.0, 100.0, true, OptionStyle::European);
+        assert_eq!(MonteCarlo::calculate_premium_mc(&option, 1000, 7).unwrap(), MonteCarlo::calculate_premium_mc(&option, 1000, 7).unwrap());
+        let forward = OptionParams { model: PricingModel::Black76Forward { funding_rate: 0.1 }, ..option.clone() };
+        let estimate = MonteCarlo::estimate(&forward, 200_000, 42).unwrap();
+        assert!((estimate.premium - BlackScholes::calculate_premium(&forward).unwrap()).abs() < 4.0 * estimate.std_error);
+
+        assert!(MonteCarlo::estimate(&option, 0, 1).is_err());
+        assert!(MonteCarlo::estimate(&params(100.0, 100.0, false, OptionStyle::American), 100, 1).is_err());
+    }
+
+    #[test]
+    fn test_day_count_scales_time_to_expiry() {
+        let with = |day_count| OptionParams { day_count, ..params(100.0, 100.0, true, OptionStyle::European) };
+        let premium = |day_count| BlackScholes::calculate_premium(&with(day_count)).unwrap();
+
+        // Fewer days per year means the same days are a longer T, so more time value
+        assert!(premium(DayCount::TradingDays) > premium(DayCount::Act360));
+        assert!(premium(DayCount::Act360) > premium(DayCount::Act365));
+        assert!(premium(DayCount::Act365) > premium(DayCount::Act36525));
+
+        // 365 days on Act365 is exactly one year, the same T as 360 days on Act360
+        let act360 = OptionParams { time_to_expiry: 360.0, ..with(DayCount::Act360) };
+        assert_eq!(act360.time_in_years(), with(DayCount::Act365).time_in_years());
+        assert_eq!(BlackScholes::calculate_premium(&act360).unwrap(), premium(DayCount::Act365));
+
+        // Theta is per day of the chosen convention, so it matches a one-day finite difference
+        for day_count in [DayCount::Act365, DayCount::Act360, DayCount::TradingDays] {
+            let theta = BlackScholes::calculate_greeks(&with(day_count)).unwrap().theta;
+            let tomorrow = OptionParams { time_to_expiry: 364.0, ..with(day_count) };
+            let decay = BlackScholes::calculate_premium(&tomorrow).unwrap() - premium(day_count);
+            assert!((theta - decay).abs() < 2e-3, "{:?}: theta {} vs {}", day_count, theta, decay);
+        }
+    }
+
+    #[test]
+    fn test_black76_forward_model() {
+        let forward = |funding_rate, is_call, style| OptionParams {
+            model: PricingModel::Black76Forward { funding_rate },
+            ..params(100.0, 105.0, is_call, style)
+        };
+        for is_call in [true, false] {
+            // Zero funding: F = S·e^{rT}, the same as pricing off spot
+            let zero_carry = BlackScholes::calculate_premium(&forward(0.0, is_call, OptionStyle::European)).unwrap();
+            let spot = BlackScholes::calculate_premium(&params(100.0, 105.0, is_call, OptionStyle::European)).unwrap();
+            assert!((zero_carry - spot).abs() < 1e-3, "call={}: {} vs {}", is_call, zero_carry, spot);
+
+            // Exercising into the future early can pay, so American is worth at least European
+            let american = BlackScholes::calculate_premium(&forward(0.0, is_call, OptionStyle::American)).unwrap();
+            assert!(american >= zero_carry - 1e-2, "call={}: {} < {}", is_call, american, zero_carry);
+        }
+
+        // Funding above r puts the forward below spot: calls cheaper, puts dearer
+        let params_high = forward(0.10, true, OptionStyle::European);
+        assert!(params_high.forward_price() < 100.0);
+        let call = BlackScholes::calculate_premium(&params_high).unwrap();
+        let put = BlackScholes::calculate_premium(&forward(0.10, false, OptionStyle::European)).unwrap();
+        assert!(call < BlackScholes::calculate_premium(&params(100.0, 105.0, true, OptionStyle::European)).unwrap());
+
+        // Black-76 put-call parity: C - P = e^{-rT}(F - K)
+        let parity = (-0.05_f64).exp() * (params_high.forward_price() - 105.0);
+        assert!((call - put - parity).abs() < 1e-3, "{} vs {}", call - put, parity);
+    }
+
+    #[test]
+    fn test_premium_bounds() {
+        // Near-zero volatility pins deep in-the-money options right at their bounds
+        let deep_call = OptionParams { volatility: 1e-4, ..params(1000.0, 1.0, true, OptionStyle::European) };
+        let deep_put = OptionParams { volatility: 1e-4, ..params(1e-3, 100.0, false, OptionStyle::European) };
+        for deep in [&deep_call, &deep_put] {
+            let premium = BlackScholes::calculate_premium(deep).unwrap();
+            check_premium_bounds(deep, premium).unwrap();
+        }
+        // The put's ceiling is the discounted strike, not the strike itself
+        let ceiling = 100.0 * (-0.05_f64).exp();
+        check_premium_bounds(&deep_put, ceiling).unwrap();
+        check_premium_bounds(&deep_call, 0.0).unwrap();
+
+        match check_premium_bounds(&deep_put, ceiling * 1.001) {
+            Err(EngineError::PricingFailed { kind, premium, bound, underlying, strike }) => {
+                assert_eq!(kind, "put");
+                assert_eq!(premium, ceiling * 1.001);
+                assert!((bound - ceiling).abs() < 1e-9);
+                assert_eq!((underlying, strike), (1e-3, 100.0));
+            }
+            other => panic!("expected PricingFailed, got {:?}", other),
+        }
+        assert!(check_premium_bounds(&deep_call, 1000.1).is_err());
+        assert!(check_premium_bounds(&deep_call, -1.0).is_err());
+        assert!(check_premium_bounds(&deep_call, f64::NAN).is_err());
+
+        // Early exercise lets an American put reach the full strike
+        let american_put = OptionParams { style: OptionStyle::American, ..deep_put.clone() };
+        check_premium_bounds(&american_put, 99.9).unwrap();
+        assert!(check_premium_bounds(&deep_put, 99.9).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::engine::PremiumResult;
+use crate::error::EngineError;
+use crate::pricing::{Greeks, breakeven};
+
+/// Rounds premiums to a quoting tick and flags ticks that are coarse next to the option's vega.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TickRounding {
+    /// Smallest quotable premium increment, in the quote currency
+    pub tick: f64,
+    /// Largest rounding move allowed, as a fraction of vega (premium per vol point)
+    pub max_vega_fraction: f64,
+}
+
+/// A premium snapped to the tick, with how far rounding moved it.
+#[derive(Debug, Clone)]
+pub struct RoundedQuote {
+    pub result: PremiumResult,
+    // Rounded minus raw premium
+    pub adjustment: f64,
+    // The adjustment exceeded `max_vega_fraction` of vega, so the tick is too coarse
+    pub too_coarse: bool,
+}
+
+/// Inputs for widening a mid premium into a bid and ask. The half-spread is
+///
+/// `min_half_spread + liquidity * (vol_points + time_vol_points * T) * |vega| + |delta| * confidence`
+///
+/// so it grows with vega and time to expiry, and with the oracle's uncertainty about the
+/// underlying carried through delta.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpreadParams {
+    /// Option vega, premium per vol point
+    pub vega: f64,
+    pub delta: f64,
+    pub time_to_expiry_years: f64,
+    /// Oracle confidence interval of the underlying, in its price units
+    pub confidence: f64,
+    /// Half-spread in vol points at `liquidity` 1
+    pub vol_points: f64,
+    /// Extra vol points of half-spread per year to expiry
+    pub time_vol_points: f64,
+    /// 1 for a normal market; higher for thinner ones
+    pub liquidity: f64,
+    pub min_half_spread: f64,
+}
+
+/// Two-sided quote around a mid premium; `bid` never goes below zero.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Quote {
+    pub bid: f64,
+    pub ask: f64,
+    pub mid: f64,
+}
+
+impl SpreadParams {
+    pub fn half_spread(&self) -> f64 {
+        let vol_points = self.vol_points + self.time_vol_points * self.time_to_expiry_years.max(0.0);
+        self.min_half_spread
+            + self.liquidity * vol_points * self.vega.abs()
+            + self.delta.abs() * self.confidence
+    }
+}
+
+/// Currency premiums are settled in, with its USD price. Feeds are USD-quoted, so this is
+/// the conversion rate from a USD premium, e.g. USDC at 0.9998 or SUI at 2.0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settlement {
+    pub currency: String,
+    pub usd_per_unit: f64,
+}
+
+impl Settlement {
+    pub fn new(currency: &str, usd_per_unit: f64) -> Result<Self> {
+        if !(usd_per_unit.is_finite() && usd_per_unit > 0.0) {
+            return Err(EngineError::InvalidParams(format!(
+                "{} must have a positive USD price, got {}", currency, usd_per_unit
+            )).into());
+        }
+        Ok(Self { currency: currency.to_string(), usd_per_unit })
+    }
+
+    /// Settling in USD itself, which needs no price.
+    pub fn usd() -> Self {
+        Self { currency: "USD".to_string(), usd_per_unit: 1.0 }
+    }
+
+    pub fn is_usd(&self) -> bool {
+        self.currency.eq_ignore_ascii_case("USD")
+    }
+}
+
+/// A premium converted into its settlement currency.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettledPremium {
+    pub currency: String,
+    pub usd_per_unit: f64,
+    pub premium: f64,
+    pub notional_premium: f64,
+}
+
+impl PremiumResult {
+    /// Bid and ask around this premium as the mid.
+    pub fn with_spread(&self, params: &SpreadParams) -> Quote {
+        let half = params.half_spread();
+        Quote { bid: (self.premium - half).max(0.0), ask: self.premium + half, mid: self.premium }
+    }
+
+    /// The premium in `settlement`'s currency. Nothing is rounded here: round the converted
+    /// figure to the settlement currency's own tick afterwards (see [`TickRounding`]), since
+    /// rounding the USD premium first would carry its rounding error through the rate.
+    pub fn settled_in(&self, settlement: &Settlement) -> SettledPremium {
+        let (premium, notional_premium) = if settlement.is_usd() {
+            (self.premium, self.notional_premium)
+        } else {
+            (self.premium / settlement.usd_per_unit, self.notional_premium / settlement.usd_per_unit)
+        };
+        SettledPremium {
+            currency: settlement.currency.clone(),
+            usd_per_unit: settlement.usd_per_unit,
+            premium,
+            notional_premium,
+        }
+    }
+}
+
+impl TickRounding {
+    pub fn new(tick: f64, max_vega_fraction: f64) -> Result<Self> {
+        if !(tick.is_finite() && tick > 0.0) {
+            return Err(EngineError::InvalidParams(format!("tick must be positive, got {}", tick)).into());
+        }
+        if !(max_vega_fraction.is_finite() && max_vega_fraction >= 0.0) {
+            return Err(EngineError::InvalidParams(format!(
+                "max_vega_fraction must be non-negative, got {}", max_vega_fraction
+            )).into());
+        }
+        Ok(Self { tick, max_vega_fraction })
+    }
+
+    /// Nearest multiple of the tick to `premium`.
+    pub fn round_premium(&self, premium: f64) -> f64 {
+        (premium / self.tick).round() * self.tick
+    }
+
+    /// Rounds `result`'s premium to the tick, carrying the change into its breakeven,
+    /// notional and underlying-denominated premiums.
+    /// `vega` is the option's premium change per vol point, as in [`crate::Greeks`].
+    pub fn round(&self, result: &PremiumResult, vega: f64) -> RoundedQuote {
+        let mut rounded = result.clone();
+        rounded.premium = self.round_premium(result.premium);
+        rounded.breakeven = breakeven(result.strike, rounded.premium, result.is_call);
+        let adjustment = rounded.premium - result.premium;
+        if result.premium != 0.0 {
+            // Keep the other quotes consistent with the rounded dollar premium
+            let ratio = rounded.premium / result.premium;
+            rounded.premium_in_underlying = result.premium_in_underlying.map(|quote| quote * ratio);
+            rounded.notional_premium = result.notional_premium * ratio;
+        }
+        RoundedQuote {
+            result: rounded,
+            adjustment,
+            too_coarse: adjustment.abs() > self.max_vega_fraction * vega.abs(),
+        }
+    }
+}
+
+/// How Greeks are rounded for display and storage: fixed decimals per Greek, or the same
+/// number of significant figures for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GreeksRounding {
+    Decimals(GreekDecimals),
+    SignificantFigures(u32),
+}
+
+/// Decimal places for each Greek. Gamma gets more by default as it is usually the smallest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GreekDecimals {
+    pub delta: u32,
+    pub gamma: u32,
+    pub vega: u32,
+    pub theta: u32,
+    pub rho: u32,
+}
+
+impl Default for GreekDecimals {
+    fn default() -> Self {
+        Self { delta: 4, gamma: 6, vega: 4, theta: 4, rho: 4 }
+    }
+}
+
+impl GreeksRounding {
+    /// `greeks` with every value rounded by this policy.
+    pub fn apply(&self, greeks: Greeks) -> Greeks {
+        match *self {
+            GreeksRounding::Decimals(decimals) => Greeks {
+                delta: round_decimals(greeks.delta, decimals.delta),
+                gamma: round_decimals(greeks.gamma, decimals.gamma),
+                vega: round_decimals(greeks.vega, decimals.vega),
+                theta: round_decimals(greeks.theta, decimals.theta),
+                rho: round_decimals(greeks.rho, decimals.rho),
+            },
+            GreeksRounding::SignificantFigures(figures) => Greeks {
+                delta: round_significant(greeks.delta, figures),
+                gamma: round_significant(greeks.gamma, figures),
+                vega: round_significant(greeks.vega, figures),
+                theta: round_significant(greeks.theta, figures),
+                rho: round_significant(greeks.rho, figures),
+            },
+        }
+    }
+
+    /// Wraps `greeks` so they serialize rounded while staying exact in memory.
+    pub fn wrap(&self, greeks: Greeks) -> RoundedGreeks {
+        RoundedGreeks { raw: greeks, rounding: *self }
+    }
+}
+
+/// Greeks that serialize with their [`GreeksRounding`] applied; `raw` has the full precision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundedGreeks {
+    raw: Greeks,
+    rounding: GreeksRounding,
+}
+
+impl RoundedGreeks {
+    pub fn raw(&self) -> Greeks {
+        self.raw
+    }
+
+    pub fn rounded(&self) -> Greeks {
+        self.rounding.apply(self.raw)
+    }
+}
+
+impl Serialize for RoundedGreeks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.rounded().serialize(serializer)
+    }
+}
+
+fn round_decimals(value: f64, decimals: u32) -> f64 {
+    round_to(value, decimals as i32)
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn round_significant(value: f64, figures: u32) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    round_to(value, figures.max(1) as i32 - 1 - magnitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::VolSource;
+
+    fn result(premium: f64) -> PremiumResult {
+        PremiumResult { symbol: "SUI".to_string(), strike: 2.0, is_call: true, premium, prob_itm: 0.5, breakeven: 2.0 + premium, premium_in_underlying: None, notional_premium: premium, timestamp: 1_700_000_000, from_cache: false, vol_source: VolSource::Oracle, inputs: None }
+    }
+
+    #[test]
+    fn test_spread_widens_with_vega_time_and_confidence() {
+        let params = SpreadParams {
+            vega: 0.002,
+            delta: 0.5,
+            time_to_expiry_years: 0.25,
+            confidence: 0.0,
+            vol_points: 1.0,
+            time_vol_points: 2.0,
+            liquidity: 1.0,
+            min_half_spread: 0.0001,
+        };
+        // 0.0001 + (1 + 2 * 0.25) * 0.002
+        let quote = result(0.12).with_spread(&params);
+        assert!((quote.ask - quote.mid - 0.0031).abs() < 1e-12);
+        assert!((quote.mid - quote.bid - 0.0031).abs() < 1e-12);
+
+        let wider = |changed: SpreadParams| result(0.12).with_spread(&changed).ask > quote.ask;
+        assert!(wider(SpreadParams { vega: 0.004, ..params }));
+        assert!(wider(SpreadParams { time_to_expiry_years: 1.0, ..params }));
+        assert!(wider(SpreadParams { confidence: 0.002, ..params }));
+        assert!(wider(SpreadParams { liquidity: 3.0, ..params }));
+
+        // A premium smaller than the half-spread is bid at zero
+        assert_eq!(result(0.001).with_spread(&params).bid, 0.0);
+    }
+
+    #[test]
+    fn test_tick_rounding_flags_coarse_ticks() {
+        // SUI-sized premium with a fine tick: the move is small next to vega
+        let fine = TickRounding::new(0.0001, 0.1).unwrap();
+        let quote = fine.round(&result(0.12346), 0.004);
+        assert!((quote.result.premium - 0.1235).abs() < 1e-12);
+        assert!((quote.adjustment - 0.00004).abs() < 1e-12);
+        assert!(!quote.too_coarse);
+
+        // A BTC-sized tick on the same option moves the quote by far more than a tenth of vega
+        let coarse = TickRounding::new(0.5, 0.1).unwrap();
+        let quote = coarse.round(&result(0.12346), 0.004);
+        assert_eq!(quote.result.premium, 0.0);
+        assert!(quote.too_coarse);
+
+        assert!(TickRounding::new(0.0, 0.1).is_err());
+        assert!(TickRounding::new(0.01, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_greeks_round_only_when_serialized() {
+        let greeks = Greeks { delta: 0.512345678, gamma: 0.0000123456, vega: 0.00234567, theta: -0.00098765, rho: 12.3456789 };
+
+        let by_decimals = GreeksRounding::Decimals(GreekDecimals::default()).wrap(greeks);
+        assert_eq!(by_decimals.raw(), greeks);
+        let json = serde_json::to_value(by_decimals).unwrap();
+        assert_eq!(json["delta"], 0.5123);
+        assert_eq!(json["gamma"], 0.000012);
+        assert_eq!(json["theta"], -0.001);
+        assert_eq!(json["rho"], 12.3457);
+
+        let by_figures = GreeksRounding::SignificantFigures(3).wrap(greeks).rounded();
+        assert_eq!(by_figures.gamma, 0.0000123);
+        assert_eq!(by_figures.theta, -0.000988);
+        assert_eq!(by_figures.rho, 12.3);
+        assert_eq!(GreeksRounding::SignificantFigures(3).apply(Greeks::default()), Greeks::default());
+
+        let policy: GreeksRounding = serde_json::from_str(r#"{"decimals": {"gamma": 8}}"#).unwrap();
+        assert_eq!(policy, GreeksRounding::Decimals(GreekDecimals { gamma: 8, ..GreekDecimals::default() }));
+    }
+
+    #[test]
+    fn test_settlement_conversion() {
+        let usd = result(0.12).settled_in(&Settlement::usd());
+        assert_eq!((usd.currency.as_str(), usd.premium, usd.notional_premium), ("USD", 0.12, 0.12));
+
+        // At $2 a SUI, a $0.12 premium is 0.06 SUI
+        let sui = result(0.12).settled_in(&Settlement::new("SUI", 2.0).unwrap());
+        assert!((sui.premium - 0.06).abs() < 1e-15);
+        assert_eq!(sui.usd_per_unit, 2.0);
+
+        assert!(Settlement::new("SUI", 0.0).is_err());
+        assert!(Settlement::new("SUI", f64::NAN).is_err());
+    }
+}
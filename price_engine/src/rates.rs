@@ -0,0 +1,234 @@
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+// Source of the annualized risk-free rate used for discounting
+#[async_trait]
+pub trait RateProvider: Send + Sync {
+    async fn risk_free_rate(&self) -> Result<f64>;
+}
+
+// Fixed rate, e.g. `EngineConfig::risk_free_rate`
+pub struct ConstantRate(pub f64);
+
+#[async_trait]
+impl RateProvider for ConstantRate {
+    async fn risk_free_rate(&self) -> Result<f64> {
+        Ok(self.0)
+    }
+}
+
+/// Risk-free rate by tenor, linearly interpolated between points and held flat beyond the
+/// ends, so a single point is a flat rate.
+///
+/// Serialized as a list of `[tenor_years, rate]` pairs, e.g. `[[0.0, 0.03], [1.0, 0.045]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<(f64, f64)>", into = "Vec<(f64, f64)>")]
+pub struct RateCurve {
+    points: Vec<(f64, f64)>, // (tenor in years, rate), ascending by tenor
+}
+
+impl RateCurve {
+    pub fn new(mut points: Vec<(f64, f64)>) -> Result<Self> {
+        if points.is_empty() {
+            bail!("A rate curve needs at least one point");
+        }
+        for &(tenor, rate) in &points {
+            if !(tenor.is_finite() && tenor >= 0.0) {
+                bail!("Rate curve tenors must be non-negative and finite, got {}", tenor);
+            }
+            if !rate.is_finite() {
+                bail!("Rate curve rates must be finite, got {} at tenor {}", rate, tenor);
+            }
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = points.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            bail!("Rate curve has more than one point at tenor {}", pair[0].0);
+        }
+        Ok(Self { points })
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Rate for discounting over `years`.
+    pub fn rate_at(&self, years: f64) -> f64 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if years <= first.0 {
+            return first.1;
+        }
+        if years >= last.0 {
+            return last.1;
+        }
+        let upper = self.points.partition_point(|&(tenor, _)| tenor <= years);
+        let (t0, r0) = self.points[upper - 1];
+        let (t1, r1) = self.points[upper];
+        r0 + (r1 - r0) * (years - t0) / (t1 - t0)
+    }
+}
+
+impl TryFrom<Vec<(f64, f64)>> for RateCurve {
+    type Error = anyhow::Error;
+
+    fn try_from(points: Vec<(f64, f64)>) -> Result<Self> {
+        Self::new(points)
+    }
+}
+
+impl From<RateCurve> for Vec<(f64, f64)> {
+    fn from(curve: RateCurve) -> Self {
+        curve.points
+    }
+}
+
+// Where to fetch a live rate from, e.g. a lending protocol's supply APY
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRateConfig {
+    pub url: String,
+    // JSON pointer to the number in the response body, e.g. `/data/0/supplyApy`; empty means the body itself
+    #[serde(default)]
+    pub json_pointer: String,
+    // Multiplier applied to the fetched number, e.g. 0.01 when the source reports percent
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+fn default_ttl_secs() -> u64 {
+    300
+}
+
+// Fetches the rate over HTTP and reuses it for `ttl_secs` before asking again
+pub struct HttpRateProvider {
+    client: reqwest::Client,
+    config: HttpRateConfig,
+    cached: Mutex<Option<(f64, Instant)>>,
+}
+
+impl HttpRateProvider {
+    pub fn new(config: HttpRateConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+            cached: Mutex::new(None),
+        }
+    }
+
+    async fn fetch(&self) -> Result<f64> {
+        let body: serde_json::Value = self.client.get(&self.config.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let value = body.pointer(&self.config.json_pointer)
+            .with_context(|| format!("No value at `{}` in rate response", self.config.json_pointer))?;
+        // Some APIs quote rates as strings
+        let rate = match value {
+            serde_json::Value::String(text) => text.parse::<f64>().ok(),
+            other => other.as_f64(),
+        }
+        .with_context(|| format!("Rate at `{}` is not a number: {}", self.config.json_pointer, value))?;
+
+        Ok(rate * self.config.scale)
+    }
+}
+
+#[async_trait]
+impl RateProvider for HttpRateProvider {
+    async fn risk_free_rate(&self) -> Result<f64> {
+        // Held across the fetch so concurrent callers wait for one request instead of each sending one
+        let mut cached = self.cached.lock().await;
+        let ttl = Duration::from_secs(self.config.ttl_secs);
+        if let Some((rate, fetched_at)) = *cached
+            && fetched_at.elapsed() < ttl
+        {
+            return Ok(rate);
+        }
+
+        let rate = self.fetch().await
+            .with_context(|| format!("Failed to fetch risk-free rate from {}", self.config.url))?;
+        *cached = Some((rate, Instant::now()));
+        Ok(rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Minimal HTTP server answering every request with `body`; returns its URL and a request counter
+    async fn serve_json(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/rate", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn test_http_rate_is_scaled_and_cached() {
+        let (url, requests) = serve_json(r#"{"data": [{"supplyApy": "4.2"}]}"#).await;
+        let provider = HttpRateProvider::new(HttpRateConfig {
+            url,
+            json_pointer: "/data/0/supplyApy".to_string(),
+            scale: 0.01,
+            ttl_secs: 60,
+        });
+
+        assert!((provider.risk_free_rate().await.unwrap() - 0.042).abs() < 1e-12);
+        assert!((provider.risk_free_rate().await.unwrap() - 0.042).abs() < 1e-12);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_http_rate_refetches_after_ttl_and_reports_bad_pointer() {
+        let (url, requests) = serve_json(r#"{"rate": 0.031}"#).await;
+        let provider = HttpRateProvider::new(HttpRateConfig {
+            url: url.clone(),
+            json_pointer: "/rate".to_string(),
+            scale: 1.0,
+            ttl_secs: 0,
+        });
+        provider.risk_free_rate().await.unwrap();
+        provider.risk_free_rate().await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let provider = HttpRateProvider::new(HttpRateConfig {
+            url,
+            json_pointer: "/missing".to_string(),
+            scale: 1.0,
+            ttl_secs: 60,
+        });
+        let err = provider.risk_free_rate().await.unwrap_err();
+        assert!(format!("{:#}", err).contains("/missing"), "{:#}", err);
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::oracle::PriceData;
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// How realized volatility is estimated from a run of recorded prices. Both return an
+/// annualized vol of log returns, scaled by how often the prices were sampled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "method")]
+pub enum VolEstimator {
+    /// Sample standard deviation, every return weighted alike
+    #[default]
+    Simple,
+    /// RiskMetrics-style exponentially weighted average of squared returns. Each older return
+    /// weighs `lambda` times the next one, so a lower lambda reacts faster to a vol spike.
+    Ewma { lambda: f64 },
+}
+
+impl VolEstimator {
+    /// Annualized realized vol over `prices`, oldest first.
+    pub fn estimate(&self, prices: &[PriceData]) -> Result<f64> {
+        if prices.len() < 3 {
+            bail!("Need at least 3 prices to estimate volatility, got {}", prices.len());
+        }
+        let span = prices[prices.len() - 1].timestamp - prices[0].timestamp;
+        if span <= 0 {
+            bail!("Prices must span some time to estimate volatility");
+        }
+        let mut returns = Vec::with_capacity(prices.len() - 1);
+        for pair in prices.windows(2) {
+            if !(pair[0].price > 0.0 && pair[1].price > 0.0) {
+                bail!("Cannot take log returns of non-positive prices");
+            }
+            returns.push((pair[1].price / pair[0].price).ln());
+        }
+        // Hermes publishes irregularly, so the average spacing stands in for the interval
+        let interval = Duration::from_secs_f64(span as f64 / returns.len() as f64);
+        self.estimate_returns(&returns, interval)
+    }
+
+    /// Annualized vol of log `returns` sampled every `interval`, oldest first. The per-sample
+    /// vol is scaled by the square root of samples per year, so minute returns are scaled
+    /// by about 725 where daily ones would be by about 19.
+    pub fn estimate_returns(&self, returns: &[f64], interval: Duration) -> Result<f64> {
+        if returns.len() < 2 {
+            bail!("Need at least 2 returns to estimate volatility, got {}", returns.len());
+        }
+        if interval.is_zero() {
+            bail!("Sampling interval must be positive");
+        }
+        let periods_per_year = SECONDS_PER_YEAR / interval.as_secs_f64();
+        let variance = match *self {
+            VolEstimator::Simple => {
+                let n = returns.len() as f64;
+                let mean = returns.iter().sum::<f64>() / n;
+                returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)
+            }
+            VolEstimator::Ewma { lambda } => {
+                if !(lambda > 0.0 && lambda < 1.0) {
+                    bail!("EWMA lambda must be between 0 and 1, got {}", lambda);
+                }
+                // Seeded with the first squared return; short-horizon returns are close enough to
+                // zero-mean that RiskMetrics skips the mean
+                returns[1..].iter().fold(returns[0].powi(2), |variance, r| lambda * variance + (1.0 - lambda) * r * r)
+            }
+        };
+        Ok((variance * periods_per_year).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    #[test]
+    fn test_ewma_reacts_to_recent_vol_spike() {
+        // 60 calm daily returns of ±1%, then 10 of ±5%
+        let returns: Vec<f64> = (0..70)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 } * if i < 60 { 0.01 } else { 0.05 })
+            .collect();
+        let simple = VolEstimator::Simple.estimate_returns(&returns, DAY).unwrap();
+        let ewma = VolEstimator::Ewma { lambda: 0.94 }.estimate_returns(&returns, DAY).unwrap();
+        assert!(ewma > simple, "EWMA {} should exceed simple {}", ewma, simple);
+        // Simple vol sits between the calm and spiked regimes
+        assert!(simple > 0.01 * 365f64.sqrt() && simple < 0.05 * 365f64.sqrt());
+
+        // From prices a day apart, the same as annualizing the returns by 365
+        let mut price = 2.0;
+        let prices: Vec<PriceData> = std::iter::once(0.0)
+            .chain(returns.iter().copied())
+            .enumerate()
+            .map(|(day, r)| {
+                price *= f64::exp(r);
+                PriceData { symbol: "SUI".into(), price, confidence: 0.0, timestamp: day as i64 * 86_400, expo: None }
+            })
+            .collect();
+        let from_prices = VolEstimator::Ewma { lambda: 0.94 }.estimate(&prices).unwrap();
+        assert!((from_prices - ewma).abs() < 1e-9);
+
+        assert!(VolEstimator::Ewma { lambda: 1.0 }.estimate_returns(&returns, DAY).is_err());
+        assert!(VolEstimator::Simple.estimate(&prices[..2]).is_err());
+    }
+
+    #[test]
+    fn test_minute_samples_annualize_to_input_vol() {
+        // A 80% vol path sampled every minute: each return is 80% / sqrt(minutes per year)
+        let per_minute = 0.8 / (365.0f64 * 24.0 * 60.0).sqrt();
+        let returns: Vec<f64> = (0..10_000).map(|i| if i % 2 == 0 { per_minute } else { -per_minute }).collect();
+        let simple = VolEstimator::Simple.estimate_returns(&returns, Duration::from_secs(60)).unwrap();
+        assert!((simple - 0.8).abs() < 1e-3, "{}", simple);
+        // Treating the same samples as daily understates the vol about 38 times over
+        let as_daily = VolEstimator::Simple.estimate_returns(&returns, DAY).unwrap();
+        assert!((simple / as_daily - (24.0f64 * 60.0).sqrt()).abs() < 1e-9);
+
+        // Recorded prices a minute apart give the same number
+        let mut price = 2.0;
+        let prices: Vec<PriceData> = std::iter::once(0.0)
+            .chain(returns.iter().copied())
+            .enumerate()
+            .map(|(minute, r)| {
+                price *= f64::exp(r);
+                PriceData { symbol: "SUI".into(), price, confidence: 0.0, timestamp: minute as i64 * 60, expo: None }
+            })
+            .collect();
+        let ewma = VolEstimator::Ewma { lambda: 0.94 }.estimate(&prices).unwrap();
+        assert!((ewma - 0.8).abs() < 1e-3, "{}", ewma);
+        assert!(VolEstimator::Simple.estimate_returns(&returns, Duration::ZERO).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::EngineError;
+use crate::oracle::{PriceData, PriceOracle};
+
+/// Oracle for backtests: serves historical prices from a CSV of
+/// `timestamp,symbol,price,confidence` rows instead of asking Hermes.
+///
+/// By default each `fetch_price` returns the symbol's next row. With a simulated clock it
+/// instead returns the latest row at or before a clock that starts at the first timestamp
+/// and runs `speed` times faster than real time.
+#[derive(Debug)]
+pub struct ReplayOracle {
+    rows: HashMap<String, Vec<PriceData>>, // ascending by timestamp
+    cursors: Mutex<HashMap<String, usize>>,
+    volatility: HashMap<String, f64>,
+    looping: bool,
+    speed: f64,
+    clock: Option<Instant>,
+}
+
+impl ReplayOracle {
+    pub fn from_csv(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read replay file {}", path.display()))?;
+        Self::from_csv_str(&raw).with_context(|| format!("Invalid replay file {}", path.display()))
+    }
+
+    /// Parses CSV rows; a `timestamp,...` header line, blank lines and `#` comments are skipped.
+    pub fn from_csv_str(csv: &str) -> Result<Self> {
+        let mut rows: HashMap<String, Vec<PriceData>> = HashMap::new();
+        for (number, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("timestamp") {
+                continue;
+            }
+            let price = parse_row(line).with_context(|| format!("line {}: {}", number + 1, line))?;
+            rows.entry(price.symbol.clone()).or_default().push(price);
+        }
+        if rows.is_empty() {
+            bail!("No price rows to replay");
+        }
+        for prices in rows.values_mut() {
+            prices.sort_by_key(|price| price.timestamp);
+        }
+        Ok(Self {
+            rows,
+            cursors: Mutex::new(HashMap::new()),
+            volatility: HashMap::new(),
+            looping: false,
+            speed: 1.0,
+            clock: None,
+        })
+    }
+
+    /// Starts over from the first row once a symbol's rows run out, instead of failing.
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// Playback speed relative to real time, for the simulated clock and `stream_prices`.
+    pub fn with_speed(mut self, speed: f64) -> Result<Self> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(EngineError::InvalidParams(format!("replay speed must be positive, got {}", speed)).into());
+        }
+        self.speed = speed;
+        Ok(self)
+    }
+
+    /// Serves prices by a simulated clock started now; see [`ReplayOracle::price_at`].
+    pub fn with_simulated_clock(mut self) -> Self {
+        self.clock = Some(Instant::now());
+        self
+    }
+
+    /// Volatility reported for `symbol`; without one the engine uses its default.
+    pub fn with_volatility(mut self, symbol: &str, volatility: f64) -> Self {
+        self.volatility.insert(symbol.to_string(), volatility);
+        self
+    }
+
+    /// Latest row for `symbol` at or before `timestamp`, or the first row if `timestamp` is
+    /// earlier than all of them. When looping, times past the last row wrap around.
+    pub fn price_at(&self, symbol: &str, timestamp: i64) -> Result<PriceData> {
+        let prices = self.prices(symbol)?;
+        let first = prices[0].timestamp;
+        let last = prices[prices.len() - 1].timestamp;
+        let timestamp = if self.looping && timestamp > last {
+            first + (timestamp - first) % (last - first + 1)
+        } else {
+            timestamp
+        };
+        let index = prices.partition_point(|price| price.timestamp <= timestamp).saturating_sub(1);
+        Ok(prices[index].clone())
+    }
+
+    fn prices(&self, symbol: &str) -> Result<&[PriceData]> {
+        self.rows.get(symbol)
+            .map(Vec::as_slice)
+            .ok_or_else(|| EngineError::UnknownSymbol(symbol.to_string()).into())
+    }
+
+    fn next_row(&self, symbol: &str) -> Result<PriceData> {
+        let prices = self.prices(symbol)?;
+        let mut cursors = self.cursors.lock().unwrap();
+        let cursor = cursors.entry(symbol.to_string()).or_default();
+        if *cursor == prices.len() {
+            if !self.looping {
+                bail!("Replay for {} ran out after {} rows", symbol, prices.len());
+            }
+            *cursor = 0;
+        }
+        *cursor += 1;
+        Ok(prices[*cursor - 1].clone())
+    }
+
+    // Where the simulated clock is now, in the replayed data's time
+    fn simulated_now(&self, started: Instant, symbol: &str) -> Result<i64> {
+        let first = self.prices(symbol)?[0].timestamp;
+        Ok(first + (started.elapsed().as_secs_f64() * self.speed) as i64)
+    }
+}
+
+#[async_trait]
+impl PriceOracle for ReplayOracle {
+    fn symbols(&self) -> Vec<String> {
+        self.rows.keys().cloned().collect()
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<PriceData> {
+        match self.clock {
+            Some(started) => self.price_at(symbol, self.simulated_now(started, symbol)?),
+            None => self.next_row(symbol),
+        }
+    }
+
+    async fn fetch_volatility(&self, symbol: &str) -> Result<f64> {
+        self.volatility.get(symbol).copied()
+            .ok_or_else(|| anyhow::anyhow!("No volatility configured for replay of {}", symbol))
+    }
+
+    // Every row in order, spaced by the gaps between their timestamps divided by the speed
+    async fn stream_prices(&self, symbol: &str) -> Result<BoxStream<'static, Result<PriceData>>> {
+        let prices = self.prices(symbol)?.to_vec();
+        let (looping, speed) = (self.looping, self.speed);
+        let state = (prices, 0usize, None::<i64>);
+        Ok(stream::unfold(state, move |(prices, index, previous)| async move {
+            let index = match index {
+                index if index < prices.len() => index,
+                _ if looping => 0,
+                _ => return None,
+            };
+            let price = prices[index].clone();
+            if let Some(previous) = previous {
+                let gap = (price.timestamp - previous).max(0) as f64 / speed;
+                tokio::time::sleep(Duration::from_secs_f64(gap)).await;
+            }
+            let timestamp = price.timestamp;
+            Some((Ok(price), (prices, index + 1, Some(timestamp))))
+        })
+        .boxed())
+    }
+}
+
+fn parse_row(line: &str) -> Result<PriceData> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [timestamp, symbol, price, confidence] = fields[..] else {
+        bail!("expected timestamp,symbol,price,confidence, got {} fields", fields.len());
+    };
+    let price: f64 = price.parse().context("invalid price")?;
+    if !(price.is_finite() && price > 0.0) {
+        bail!("price must be positive, got {}", price);
+    }
+    Ok(PriceData {
+        symbol: symbol.to_string(),
+        price,
+        timestamp: timestamp.parse().context("invalid timestamp")?,
+        confidence: confidence.parse().context("invalid confidence")?,
+        expo: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HISTORY: &str = "\
+timestamp,symbol,price,confidence
+1700000120,SUI,2.2,0.002
+1700000000,SUI,2.0,0.002
+1700000060,SUI,2.1,0.002
+1700000000,BTC,60000,30
+";
+
+    #[tokio::test]
+    async fn test_replay_serves_rows_in_order() {
+        let oracle = ReplayOracle::from_csv_str(HISTORY).unwrap();
+        let mut symbols = oracle.symbols();
+        symbols.sort();
+        assert_eq!(symbols, ["BTC", "SUI"]);
+
+        for expected in [2.0, 2.1, 2.2] {
+            assert_eq!(oracle.fetch_price("SUI").await.unwrap().price, expected);
+        }
+        assert!(oracle.fetch_price("SUI").await.is_err());
+        assert_eq!(oracle.fetch_price("BTC").await.unwrap().confidence, 30.0);
+        assert!(oracle.fetch_volatility("SUI").await.is_err());
+
+        let looping = ReplayOracle::from_csv_str(HISTORY).unwrap().looping().with_volatility("SUI", 0.9);
+        let prices: Vec<f64> = futures::future::join_all((0..4).map(|_| looping.fetch_price("SUI"))).await
+            .into_iter()
+            .map(|price| price.unwrap().price)
+            .collect();
+        assert_eq!(prices, [2.0, 2.1, 2.2, 2.0]);
+        assert_eq!(looping.fetch_volatility("SUI").await.unwrap(), 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_replay_by_simulated_clock() {
+        let oracle = ReplayOracle::from_csv_str(HISTORY).unwrap();
+        assert_eq!(oracle.price_at("SUI", 1_699_999_000).unwrap().price, 2.0);
+        assert_eq!(oracle.price_at("SUI", 1_700_000_059).unwrap().price, 2.0);
+        assert_eq!(oracle.price_at("SUI", 1_700_000_060).unwrap().price, 2.1);
+        assert_eq!(oracle.price_at("SUI", 1_700_009_999).unwrap().price, 2.2);
+
+        // A 121 second cycle: 60 seconds past the end lands a minute into the data again
+        let looping = ReplayOracle::from_csv_str(HISTORY).unwrap().looping();
+        assert_eq!(looping.price_at("SUI", 1_700_000_121 + 60).unwrap().price, 2.1);
+
+        // The clock starts at the first row, so until it has run a minute that row is served
+        let clocked = ReplayOracle::from_csv_str(HISTORY).unwrap().with_simulated_clock();
+        assert_eq!(clocked.fetch_price("SUI").await.unwrap().price, 2.0);
+        assert_eq!(clocked.fetch_price("SUI").await.unwrap().price, 2.0);
+
+        // Streaming at 600x replays the two one-minute gaps in about 0.2s
+        let fast = ReplayOracle::from_csv_str(HISTORY).unwrap().with_speed(600.0).unwrap();
+        let started = Instant::now();
+        let streamed: Vec<f64> = fast.stream_prices("SUI").await.unwrap()
+            .map(|price| price.unwrap().price)
+            .collect()
+            .await;
+        assert_eq!(streamed, [2.0, 2.1, 2.2]);
+        assert!(started.elapsed() >= Duration::from_millis(190));
+        assert!(ReplayOracle::from_csv_str(HISTORY).unwrap().with_speed(0.0).is_err());
+    }
+
+    #[test]
+    fn test_replay_rejects_malformed_rows() {
+        let err = ReplayOracle::from_csv_str("1700000000,SUI,abc,0\n").unwrap_err();
+        assert!(format!("{:#}", err).contains("line 1"), "{:#}", err);
+        assert!(ReplayOracle::from_csv_str("1700000000,SUI,2.0\n").is_err());
+        assert!(ReplayOracle::from_csv_str("timestamp,symbol,price,confidence\n").is_err());
+        assert!(ReplayOracle::from_csv("/nonexistent/replay.csv").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::warn;
+
+use crate::error::EngineError;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    // Delay before retry number `attempt` (1-based): base * 2^(attempt-1), half of it randomized
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.base_delay.saturating_mul(1 << (attempt - 1).min(16));
+        let half = backoff / 2;
+        half + half.mul_f64(rand::random::<f64>())
+    }
+}
+
+// Connection and stream failures are worth retrying; a feed the caller got wrong is not
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    if let Some(engine_err) = err.downcast_ref::<EngineError>() {
+        return engine_err.is_retryable();
+    }
+    if let Some(pyth_hermes_client::Error::ResponseStatus(status_err)) = err.downcast_ref() {
+        return !status_err.status().is_some_and(|status| status.is_client_error());
+    }
+    true
+}
+
+/// Runs `op` until it succeeds, fails with a non-retryable error, or `max_attempts` is reached.
+pub async fn retry<T, F, Fut>(policy: RetryPolicy, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) if !is_retryable(&err) => return Err(err),
+            Err(err) if attempt >= max_attempts => {
+                return Err(err.context(format!("Giving up after {} attempts", attempt)));
+            }
+            Err(err) => {
+                warn!(attempt, max_attempts, error = %format!("{:#}", err), "Oracle fetch failed, retrying");
+                sleep(policy.delay(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const FAST: RetryPolicy = RetryPolicy { max_attempts: 3, base_delay: Duration::from_millis(1) };
+
+    #[tokio::test]
+    async fn test_retry_succeeds_after_two_failures() {
+        let calls = AtomicU32::new(0);
+        let price = retry(FAST, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(anyhow::anyhow!("connection reset")),
+                _ => Ok(2.5),
+            }
+        }).await.unwrap();
+
+        assert_eq!(price, 2.5);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_reports_attempts_on_final_failure() {
+        let calls = AtomicU32::new(0);
+        let err = retry(FAST, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow::anyhow!("stream closed"))
+        }).await.unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(err.to_string().contains("3 attempts"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_symbol_is_not_retried() {
+        let calls = AtomicU32::new(0);
+        let err = retry(FAST, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(EngineError::UnknownSymbol("DOGE".to_string()).into())
+        }).await.unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(err.downcast_ref(), Some(EngineError::UnknownSymbol(_))));
+    }
+
+    #[test]
+    fn test_backoff_grows_exponentially() {
+        let policy = RetryPolicy { max_attempts: 5, base_delay: Duration::from_millis(100) };
+        for attempt in 1..=4 {
+            let full = Duration::from_millis(100 << (attempt - 1));
+            let delay = policy.delay(attempt);
+            assert!(delay >= full / 2 && delay <= full, "attempt {}: {:?}", attempt, delay);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! JSON-RPC 2.0 over `POST /rpc`, for clients that already speak it. Serves `get_premium`,
+//! `get_curve` and `get_price` with the same parameters as the matching REST endpoints.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::engine::OptionsPricingEngine;
+use crate::error::EngineError;
+use crate::server::{CurveQuery, PremiumQuery};
+
+// Codes from the specification
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+// Implementation-defined server errors, from the -32000 to -32099 range the spec sets aside
+pub const UNKNOWN_SYMBOL: i64 = -32001;
+pub const ENGINE_ERROR: i64 = -32000;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(err: anyhow::Error) -> Self {
+        let code = match err.downcast_ref::<EngineError>() {
+            Some(EngineError::UnknownSymbol(_)) => UNKNOWN_SYMBOL,
+            Some(EngineError::InvalidParams(_)) => INVALID_PARAMS,
+            _ => ENGINE_ERROR,
+        };
+        Self::new(code, format!("{:#}", err))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    id: Value,
+}
+
+impl RpcResponse {
+    fn new(id: Value, outcome: Result<Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self { jsonrpc: "2.0", result, error, id }
+    }
+}
+
+/// The `/rpc` route, for merging into the REST router so both share one engine.
+pub fn routes() -> Router<Arc<OptionsPricingEngine>> {
+    Router::new().route("/rpc", post(rpc))
+}
+
+async fn rpc(State(engine): State<Arc<OptionsPricingEngine>>, body: String) -> Response {
+    match handle(&engine, &body).await {
+        Some(response) => Json(response).into_response(),
+        // Only notifications: the spec has nothing to send back
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+/// Answers one JSON-RPC request or batch. None when there is nothing to reply with, because
+/// every request was a notification (no `id`).
+pub async fn handle(engine: &OptionsPricingEngine, body: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(err) => return Some(reply(Value::Null, Err(RpcError::new(PARSE_ERROR, err.to_string())))),
+    };
+    match request {
+        Value::Array(batch) if batch.is_empty() => {
+            Some(reply(Value::Null, Err(RpcError::new(INVALID_REQUEST, "empty batch"))))
+        }
+        Value::Array(batch) => {
+            let responses: Vec<Value> = futures::future::join_all(batch.into_iter().map(|call| handle_one(engine, call)))
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        call => handle_one(engine, call).await,
+    }
+}
+
+async fn handle_one(engine: &OptionsPricingEngine, call: Value) -> Option<Value> {
+    let Value::Object(mut call) = call else {
+        return Some(reply(Value::Null, Err(RpcError::new(INVALID_REQUEST, "request must be an object"))));
+    };
+    // A missing id makes a notification; an explicit null is still answered
+    let id = call.remove("id");
+    let outcome = match (call.remove("jsonrpc"), call.remove("method")) {
+        (Some(Value::String(version)), Some(Value::String(method))) if version == "2.0" => {
+            dispatch(engine, &method, call.remove("params").unwrap_or(Value::Null)).await
+        }
+        _ => Err(RpcError::new(INVALID_REQUEST, r#"expected "jsonrpc": "2.0" and a string "method""#)),
+    };
+    id.map(|id| reply(id, outcome))
+}
+
+async fn dispatch(engine: &OptionsPricingEngine, method: &str, params: Value) -> Result<Value, RpcError> {
+    match method {
+        "get_premium" => {
+            let query: PremiumQuery = parse_params(params)?;
+            to_result(&query.price(engine).await?)
+        }
+        "get_curve" => {
+            let query: CurveQuery = parse_params(params)?;
+            let strike_range = query.strike_range().map_err(|reason| RpcError::new(INVALID_PARAMS, reason))?;
+            to_result(&engine.calculate_premium_curve(&query.symbol, query.days, strike_range).await?)
+        }
+        "get_price" => {
+            #[derive(serde::Deserialize)]
+            struct PriceParams {
+                symbol: String,
+            }
+            let params: PriceParams = parse_params(params)?;
+            to_result(&engine.latest_price(&params.symbol).await?)
+        }
+        other => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method {}", other))),
+    }
+}
+
+// Named parameters only, e.g. `{"symbol": "SUI"}`
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn to_result(result: &impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(result).map_err(|err| RpcError::new(ENGINE_ERROR, err.to_string()))
+}
+
+fn reply(id: Value, outcome: Result<Value, RpcError>) -> Value {
+    serde_json::to_value(RpcResponse::new(id, outcome)).expect("RPC responses are plain JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::EngineConfig;
+    use crate::mock_oracle::MockOracle;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn engine() -> OptionsPricingEngine {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        OptionsPricingEngine::new(oracle, EngineConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_rpc_methods() {
+        let engine = engine();
+        let premium = handle(&engine, r#"{"jsonrpc": "2.0", "id": 1, "method": "get_premium",
+            "params": {"symbol": "SUI", "strike": 2.0, "days": 7, "type": "call"}}"#).await.unwrap();
+        assert_eq!(premium["id"], 1);
+        assert!(premium["result"]["premium"].as_f64().unwrap() > 0.0);
+        assert!(premium.get("error").is_none());
+
+        let curve = handle(&engine, r#"{"jsonrpc": "2.0", "id": "c", "method": "get_curve",
+            "params": {"symbol": "SUI", "days": 7, "min": 1.5, "max": 2.5, "step": 0.5}}"#).await.unwrap();
+        assert_eq!(curve["result"].as_array().unwrap().len(), 6);
+
+        // A batch answers every call but the notification, in order
+        let batch = handle(&engine, r#"[
+            {"jsonrpc": "2.0", "id": 2, "method": "get_price", "params": {"symbol": "SUI"}},
+            {"jsonrpc": "2.0", "method": "get_price", "params": {"symbol": "SUI"}},
+            {"jsonrpc": "2.0", "id": 3, "method": "get_price", "params": {"symbol": "SUI"}}
+        ]"#).await.unwrap();
+        let ids: Vec<_> = batch.as_array().unwrap().iter().map(|response| response["id"].clone()).collect();
+        assert_eq!(ids, [2, 3]);
+        assert_eq!(batch[0]["result"]["price"], 2.0);
+        assert!(handle(&engine, r#"{"jsonrpc": "2.0", "method": "get_price", "params": {"symbol": "SUI"}}"#).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_objects() {
+        let engine = engine();
+        let code = |body: &'static str| {
+            let engine = &engine;
+            async move { handle(engine, body).await.unwrap()["error"]["code"].as_i64().unwrap() }
+        };
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "id": 1, "method": "get_price", "params": {"symbol": "DOGE"}}"#).await, UNKNOWN_SYMBOL);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "id": 1, "method": "get_price", "params": {}}"#).await, INVALID_PARAMS);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "id": 1, "method": "get_premium",
+            "params": {"symbol": "SUI", "strike": -1.0, "days": 7, "type": "put"}}"#).await, INVALID_PARAMS);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "id": 1, "method": "get_curve",
+            "params": {"symbol": "SUI", "days": 7, "min": 3.0, "max": 2.0, "step": 0.5}}"#).await, INVALID_PARAMS);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "id": 1, "method": "get_vol"}"#).await, METHOD_NOT_FOUND);
+        assert_eq!(code(r#"{"jsonrpc": "1.0", "id": 1, "method": "get_price"}"#).await, INVALID_REQUEST);
+        assert_eq!(code("[]").await, INVALID_REQUEST);
+        assert_eq!(code("{not json").await, PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_is_served_next_to_rest() {
+        let router = crate::server::router(Arc::new(engine()));
+        let request = Request::builder()
+            .method("POST")
+            .uri("/rpc")
+            .body(Body::from(r#"{"jsonrpc": "2.0", "id": 7, "method": "get_price", "params": {"symbol": "SUI"}}"#))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let reply: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!((reply["id"].clone(), reply["result"]["symbol"].clone()), (Value::from(7), Value::from("SUI")));
+    }
+}
@@ -0,0 +1,543 @@
+use anyhow::Result;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::chain::OptionChain;
+use crate::engine::{OptionsPricingEngine, PremiumResult};
+use crate::error::EngineError;
+use crate::metrics::metrics;
+use crate::oracle::PriceData;
+use crate::pricing::{BlackScholes, Expiry, OptionParams, PricedOption};
+use crate::streamer::PriceSubscription;
+use crate::surface::Surface;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+impl OptionKind {
+    pub fn is_call(self) -> bool {
+        matches!(self, OptionKind::Call)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PremiumQuery {
+    pub symbol: String,
+    pub strike: f64,
+    // Fractional for intraday options, e.g. 0.25 for six hours
+    pub days: f64,
+    #[serde(rename = "type")]
+    pub kind: OptionKind,
+    // Price at this volatility instead of the engine's usual source
+    #[serde(default)]
+    pub volatility: Option<f64>,
+}
+
+impl PremiumQuery {
+    /// Prices the query, at its volatility override when it has one.
+    pub async fn price(&self, engine: &OptionsPricingEngine) -> Result<PremiumResult> {
+        match self.volatility {
+            Some(volatility) => {
+                engine
+                    .calculate_option_premium_with_volatility(&self.symbol, self.strike, self.days, self.kind.is_call(), volatility)
+                    .await
+            }
+            None => engine.calculate_option_premium(&self.symbol, self.strike, self.days, self.kind.is_call()).await,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SymbolsQuery {
+    // Leave feed ids out, e.g. for a public deployment
+    #[serde(default)]
+    pub redact: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurveQuery {
+    pub symbol: String,
+    // Fractional for intraday options, as for /premium
+    pub days: f64,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl CurveQuery {
+    /// The `(min, max, step)` strike range, or why it isn't a usable one.
+    pub fn strike_range(&self) -> Result<(f64, f64, f64), &'static str> {
+        if !(self.min.is_finite() && self.max.is_finite() && self.step.is_finite()) {
+            return Err("min, max and step must be finite numbers");
+        }
+        if self.min <= 0.0 || self.min > self.max {
+            return Err("expected 0 < min <= max");
+        }
+        if self.step <= 0.0 {
+            return Err("step must be positive");
+        }
+        Ok((self.min, self.max, self.step))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SurfaceQuery {
+    pub symbol: String,
+    pub expiries: String, // comma-separated days, e.g. "7,14,30" or "0.5,7"
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    #[serde(default)]
+    pub greeks: bool,
+}
+
+// Error body returned by every endpoint: `{"error": "..."}`
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self { status: StatusCode::BAD_REQUEST, message: message.into() }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        // Unknown symbols and bad inputs are the caller's fault; a stalled oracle is a gateway timeout; anything else is unavailable
+        let status = match err.downcast_ref::<EngineError>() {
+            Some(EngineError::UnknownSymbol(_) | EngineError::InvalidParams(_)) => StatusCode::BAD_REQUEST,
+            Some(EngineError::Timeout { .. }) => StatusCode::GATEWAY_TIMEOUT,
+            // Refusing to quote off an unsure oracle is a temporary outage
+            Some(EngineError::LowConfidence { .. }) => StatusCode::SERVICE_UNAVAILABLE,
+            // Hermes answered, just not with anything we can use
+            Some(EngineError::NoParsedData { .. } | EngineError::UnverifiedUpdate { .. }) => StatusCode::BAD_GATEWAY,
+            // Offline with nothing seeded for the symbol
+            Some(EngineError::NoCachedPrice(_)) => StatusCode::SERVICE_UNAVAILABLE,
+            // A premium outside its no-arbitrage bounds is our bug
+            Some(EngineError::PricingFailed { .. }) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        Self { status, message: format!("{:#}", err) }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+/// Every REST route, plus `POST /rpc` when built with the `rpc` feature.
+pub fn router(engine: Arc<OptionsPricingEngine>) -> Router {
+    let routes = Router::new()
+        .route("/premium", get(premium))
+        .route("/price", post(price))
+        .route("/curve", get(curve))
+        .route("/chain", get(chain))
+        .route("/surface", get(surface))
+        .route("/prices", get(last_prices))
+        .route("/symbols", get(symbols))
+        .route("/ws/premium", get(premium_ws))
+        .route("/metrics", get(prometheus_metrics))
+        .route("/health", get(health));
+    #[cfg(feature = "rpc")]
+    let routes = routes.merge(crate::rpc::routes());
+    routes.with_state(engine)
+}
+
+/// Serves until `engine.shutdown()` is called, then stops accepting and waits for
+/// in-flight requests and WebSocket streams to finish.
+pub async fn serve(engine: Arc<OptionsPricingEngine>, addr: SocketAddr) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    serve_on(listener, engine).await
+}
+
+async fn serve_on(listener: tokio::net::TcpListener, engine: Arc<OptionsPricingEngine>) -> Result<()> {
+    let shutdown = engine.clone();
+    axum::serve(listener, router(engine))
+        .with_graceful_shutdown(async move { shutdown.shutdown_requested().await })
+        .await?;
+    Ok(())
+}
+
+async fn premium(
+    State(engine): State<Arc<OptionsPricingEngine>>,
+    Query(query): Query<PremiumQuery>,
+) -> Result<Json<PremiumResult>, ApiError> {
+    validate_strike(query.strike)?;
+    Ok(Json(query.price(&engine).await?))
+}
+
+// Prices a posted `OptionParams` as is: no oracle, same answer every time. The only engine
+// setting it honours is `greeks_rounding`
+async fn price(
+    State(engine): State<Arc<OptionsPricingEngine>>,
+    params: Result<Json<OptionParams>, JsonRejection>,
+) -> Result<Json<PricedOption>, ApiError> {
+    let Json(params) = params.map_err(|rejection| ApiError::bad_request(rejection.body_text()))?;
+    let mut priced = BlackScholes::price(&params)?;
+    if let Some(rounding) = &engine.config().greeks_rounding {
+        priced.greeks = rounding.apply(priced.greeks);
+    }
+    Ok(Json(priced))
+}
+
+// Same query as `/premium`; pushes a `PremiumResult` frame on every tick of the underlying feed
+async fn premium_ws(
+    ws: WebSocketUpgrade,
+    State(engine): State<Arc<OptionsPricingEngine>>,
+    Query(query): Query<PremiumQuery>,
+) -> Result<Response, ApiError> {
+    validate_strike(query.strike)?;
+    if let Some(volatility) = query.volatility
+        && !(volatility.is_finite() && volatility >= 0.0)
+    {
+        return Err(ApiError::bad_request("volatility must be a non-negative number"));
+    }
+    let expiry = engine.listed_expiry(query.days)?;
+    let subscription = engine.subscribe(&query.symbol)?;
+    Ok(ws.on_upgrade(move |socket| stream_premiums(engine, query, expiry, subscription, socket)))
+}
+
+async fn stream_premiums(
+    engine: Arc<OptionsPricingEngine>,
+    query: PremiumQuery,
+    expiry: Expiry,
+    subscription: PriceSubscription,
+    mut socket: WebSocket,
+) {
+    let premiums = engine.premiums_on(
+        subscription,
+        query.strike,
+        expiry,
+        query.kind.is_call(),
+        query.volatility,
+    );
+    let mut premiums = std::pin::pin!(premiums);
+    loop {
+        tokio::select! {
+            priced = premiums.next() => {
+                let Some(priced) = priced else { break };
+                let frame = match priced.and_then(|result| Ok(serde_json::to_string(&result)?)) {
+                    Ok(frame) => frame,
+                    Err(err) => serde_json::json!({ "error": format!("{:#}", err) }).to_string(),
+                };
+                if socket.send(Message::Text(frame.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = engine.shutdown_requested() => {
+                // Close the socket properly so the graceful shutdown isn't left waiting on us
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+        }
+    }
+    // Dropping the subscription here stops the upstream stream if we were its last subscriber
+}
+
+// Cached price per symbol, one consistent snapshot
+async fn last_prices(State(engine): State<Arc<OptionsPricingEngine>>) -> Json<HashMap<String, PriceData>> {
+    Json(engine.get_all_last_prices())
+}
+
+// Priceable symbols, sorted, each with its feed id (null when redacted or the oracle has none)
+async fn symbols(
+    State(engine): State<Arc<OptionsPricingEngine>>,
+    Query(query): Query<SymbolsQuery>,
+) -> Json<BTreeMap<String, Option<String>>> {
+    let mut feeds = engine.supported_feeds();
+    if query.redact {
+        feeds.values_mut().for_each(|feed_id| *feed_id = None);
+    }
+    Json(feeds)
+}
+
+// 200 when every feed answers with a price no older than `max_price_age_secs`, 503 otherwise
+async fn health(State(engine): State<Arc<OptionsPricingEngine>>) -> Result<Response, ApiError> {
+    let report = engine.health_check().await?;
+    let status = if report.is_healthy(engine.config().max_price_age_secs) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok((status, Json(report)).into_response())
+}
+
+async fn prometheus_metrics() -> impl IntoResponse {
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics().render(),
+    )
+}
+
+fn validate_strike(strike: f64) -> Result<(), ApiError> {
+    if !strike.is_finite() || strike <= 0.0 {
+        return Err(ApiError::bad_request("strike must be a positive number"));
+    }
+    Ok(())
+}
+
+async fn curve(
+    State(engine): State<Arc<OptionsPricingEngine>>,
+    Query(query): Query<CurveQuery>,
+) -> Result<Json<Vec<PremiumResult>>, ApiError> {
+    let strike_range = query.strike_range().map_err(ApiError::bad_request)?;
+    let curve = engine.calculate_premium_curve(&query.symbol, query.days, strike_range).await?;
+    Ok(Json(curve))
+}
+
+// Takes the same query as /curve
+async fn chain(
+    State(engine): State<Arc<OptionsPricingEngine>>,
+    Query(query): Query<CurveQuery>,
+) -> Result<Json<OptionChain>, ApiError> {
+    let strike_range = query.strike_range().map_err(ApiError::bad_request)?;
+    let mut chain = engine.build_chain(&query.symbol, query.days, strike_range).await?;
+    if let Some(rounding) = &engine.config().greeks_rounding {
+        chain.round_greeks(rounding);
+    }
+    Ok(Json(chain))
+}
+
+async fn surface(
+    State(engine): State<Arc<OptionsPricingEngine>>,
+    Query(query): Query<SurfaceQuery>,
+) -> Result<Json<Surface>, ApiError> {
+    let expiries = query.expiries
+        .split(',')
+        .map(|days| days.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ApiError::bad_request("expiries must be a comma-separated list of days"))?;
+
+    let strike_range = (query.min, query.max, query.step);
+    let mut surface = if query.greeks {
+        engine.build_surface_with_greeks(&query.symbol, &expiries, strike_range).await?
+    } else {
+        engine.build_surface(&query.symbol, &expiries, strike_range).await?
+    };
+    if let Some(rounding) = &engine.config().greeks_rounding {
+        surface.round_greeks(rounding);
+    }
+    Ok(Json(surface))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::EngineConfig;
+    use crate::mock_oracle::MockOracle;
+    use crate::oracle::PythOracle;
+    use crate::quote::GreeksRounding;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn test_router() -> Router {
+        let engine = OptionsPricingEngine::new(Arc::new(PythOracle::new()), EngineConfig::default());
+        router(Arc::new(engine))
+    }
+
+    async fn status_of(uri: &str) -> StatusCode {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        test_router().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_unknown_symbol_is_bad_request() {
+        let status = status_of("/premium?symbol=DOGE&strike=1.0&days=7&type=call").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_serve_returns_after_shutdown() {
+        let engine = Arc::new(OptionsPricingEngine::new(Arc::new(PythOracle::new()), EngineConfig::default()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = tokio::spawn(serve_on(listener, engine.clone()));
+
+        engine.shutdown().await;
+        let result = tokio::time::timeout(std::time::Duration::from_secs(1), server).await;
+        assert!(result.unwrap().unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = Arc::new(OptionsPricingEngine::new(oracle.clone(), EngineConfig::default()));
+        let request = || Request::builder().uri("/health").body(Body::empty()).unwrap();
+
+        let response = router(engine.clone()).oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        oracle.fail_price("SUI", "hermes unreachable");
+        let response = router(engine).oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_symbols_endpoint() {
+        let body = |uri: &'static str| async move {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let response = test_router().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+
+        let symbols = body("/symbols").await;
+        assert_eq!(symbols["SUI"], "0x23d7315113f5b1d3ba7a83604c44b94d79f4fd69af77f804fc7f920a6dc65744");
+        let redacted = body("/symbols?redact=true").await;
+        assert!(redacted["SUI"].is_null());
+        assert_eq!(redacted.as_object().unwrap().len(), symbols.as_object().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn test_chain_endpoint() {
+        let engine = OptionsPricingEngine::new(Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5)), EngineConfig::default());
+        let request = Request::builder().uri("/chain?symbol=SUI&days=7&min=1.5&max=2.5&step=0.5").body(Body::empty()).unwrap();
+        let response = router(Arc::new(engine)).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let chain: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let rows = chain["rows"].as_array().unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!((rows[0]["call"]["moneyness"].clone(), rows[1]["put"]["moneyness"].clone()), ("ITM".into(), "ATM".into()));
+        assert!(rows[2]["put"]["greeks"]["delta"].as_f64().unwrap() < 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_price_endpoint_is_pure() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let engine = Arc::new(OptionsPricingEngine::new(oracle.clone(), EngineConfig::default()));
+        let post = |body: String| {
+            let engine = engine.clone();
+            async move {
+                let request = Request::builder()
+                    .method("POST")
+                    .uri("/price")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap();
+                let response = router(engine).oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<serde_json::Value>(&bytes).unwrap())
+            }
+        };
+        let params = serde_json::json!({
+            "underlying_price": 100.0,
+            "strike_price": 100.0,
+            "time_to_expiry": 365.0,
+            "volatility": 0.2,
+            "risk_free_rate": 0.05,
+            "is_call": true,
+        });
+
+        let (status, body) = post(params.to_string()).await;
+        assert_eq!(status, StatusCode::OK);
+        let expected = BlackScholes::price(&serde_json::from_value(params.clone()).unwrap()).unwrap();
+        assert_eq!(body["premium"].as_f64().unwrap(), expected.premium);
+        assert_eq!(body["greeks"]["delta"].as_f64().unwrap(), expected.greeks.delta);
+        assert_eq!(oracle.price_fetches(), 0);
+
+        let mut negative_vol = params.clone();
+        negative_vol["volatility"] = (-0.2).into();
+        let (status, body) = post(negative_vol.to_string()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("volatility"), "{}", body);
+        let (status, _) = post(r#"{"underlying_price": 100.0}"#.to_string()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_greeks_rounding_applies_to_responses() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let rounding = GreeksRounding::SignificantFigures(3);
+        let config = EngineConfig { greeks_rounding: Some(rounding), ..EngineConfig::default() };
+        let engine = Arc::new(OptionsPricingEngine::new(oracle, config));
+        let body = |request: Request<Body>| {
+            let engine = engine.clone();
+            async move {
+                let response = router(engine).oneshot(request).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+            }
+        };
+        let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let gamma = |value: &serde_json::Value| value["gamma"].as_f64().unwrap();
+
+        // The engine keeps full precision; only the responses are rounded
+        let chain = engine.build_chain("SUI", 7, (1.5, 2.5, 0.5)).await.unwrap();
+        let raw = chain.rows[0].call.greeks;
+        assert_ne!(rounding.apply(raw), raw);
+        let served = body(get("/chain?symbol=SUI&days=7&min=1.5&max=2.5&step=0.5")).await;
+        assert_eq!(gamma(&served["rows"][0]["call"]["greeks"]), rounding.apply(raw).gamma);
+
+        let surface = engine.build_surface_with_greeks("SUI", &[7], (1.5, 2.5, 0.5)).await.unwrap();
+        let raw = surface.put_greeks.unwrap()[0][2];
+        let served = body(get("/surface?symbol=SUI&expiries=7&min=1.5&max=2.5&step=0.5&greeks=true")).await;
+        assert_eq!(gamma(&served["put_greeks"][0][2]), rounding.apply(raw).gamma);
+
+        let params = serde_json::json!({
+            "underlying_price": 100.0,
+            "strike_price": 100.0,
+            "time_to_expiry": 30.0,
+            "volatility": 0.2,
+            "risk_free_rate": 0.05,
+            "is_call": true,
+        });
+        let raw = BlackScholes::price(&serde_json::from_value(params.clone()).unwrap()).unwrap().greeks;
+        let request = Request::builder()
+            .method("POST")
+            .uri("/price")
+            .header("content-type", "application/json")
+            .body(Body::from(params.to_string()))
+            .unwrap();
+        assert_eq!(gamma(&body(request).await["greeks"]), rounding.apply(raw).gamma);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        assert_eq!(status_of("/metrics").await, StatusCode::OK);
+        assert_eq!(status_of("/prices").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_bad_params_are_bad_request() {
+        assert_eq!(status_of("/premium?symbol=SUI&strike=abc&days=7&type=call").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/premium?symbol=SUI&strike=2.0&days=7&type=straddle").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/premium?symbol=SUI&strike=-1.0&days=7&type=put").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/curve?symbol=SUI&days=7&min=1.0&max=2.0&step=0").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/curve?symbol=SUI&days=7&min=3.0&max=2.0&step=0.1").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/chain?symbol=SUI&days=7&min=3.0&max=2.0&step=0.1").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/surface?symbol=SUI&expiries=7,x&min=1.0&max=2.0&step=0.1").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/surface?symbol=SUI&expiries=0,7&min=1.0&max=2.0&step=0.1").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status_of("/curve?symbol=SUI&days=-0.5&min=1.0&max=2.0&step=0.5").await, StatusCode::BAD_REQUEST);
+        // Intraday expiries are accepted everywhere a day count is taken; whether they then
+        // price depends on the oracle being reachable
+        assert_ne!(status_of("/curve?symbol=SUI&days=0.5&min=1.0&max=2.0&step=0.5").await, StatusCode::BAD_REQUEST);
+        assert_ne!(status_of("/surface?symbol=SUI&expiries=0.5,7&min=1.0&max=2.0&step=0.5").await, StatusCode::BAD_REQUEST);
+    }
+}
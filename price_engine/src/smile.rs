@@ -0,0 +1,89 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Implied volatility by strike for one expiry-agnostic smile. Between points the vol is
+/// linearly interpolated; beyond the outermost strikes it is held flat.
+///
+/// Serialized as a list of `[strike, vol]` pairs, e.g. `[[1.5, 0.65], [2.0, 0.5], [2.5, 0.6]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<(f64, f64)>", into = "Vec<(f64, f64)>")]
+pub struct VolSmile {
+    points: Vec<(f64, f64)>, // (strike, vol), ascending by strike
+}
+
+impl VolSmile {
+    pub fn new(mut points: Vec<(f64, f64)>) -> Result<Self> {
+        if points.is_empty() {
+            bail!("A volatility smile needs at least one point");
+        }
+        for &(strike, vol) in &points {
+            if !(strike.is_finite() && strike > 0.0) {
+                bail!("Smile strikes must be positive and finite, got {}", strike);
+            }
+            if !(vol.is_finite() && vol >= 0.0) {
+                bail!("Smile vols must be non-negative and finite, got {} at strike {}", vol, strike);
+            }
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = points.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            bail!("Smile has more than one point at strike {}", pair[0].0);
+        }
+        Ok(Self { points })
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    pub fn vol_at(&self, strike: f64) -> f64 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if strike <= first.0 {
+            return first.1;
+        }
+        if strike >= last.0 {
+            return last.1;
+        }
+        // First point strictly above `strike`; the one before it is at or below
+        let upper = self.points.partition_point(|&(k, _)| k <= strike);
+        let (k0, v0) = self.points[upper - 1];
+        let (k1, v1) = self.points[upper];
+        v0 + (v1 - v0) * (strike - k0) / (k1 - k0)
+    }
+}
+
+impl TryFrom<Vec<(f64, f64)>> for VolSmile {
+    type Error = anyhow::Error;
+
+    fn try_from(points: Vec<(f64, f64)>) -> Result<Self> {
+        Self::new(points)
+    }
+}
+
+impl From<VolSmile> for Vec<(f64, f64)> {
+    fn from(smile: VolSmile) -> Self {
+        smile.points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vol_smile_interpolates_and_holds_ends_flat() {
+        let smile = VolSmile::new(vec![(2.5, 0.6), (1.5, 0.7), (2.0, 0.5)]).unwrap();
+        assert_eq!(smile.points()[0], (1.5, 0.7));
+
+        assert_eq!(smile.vol_at(2.0), 0.5);
+        assert!((smile.vol_at(1.75) - 0.6).abs() < 1e-12);
+        assert!((smile.vol_at(2.25) - 0.55).abs() < 1e-12);
+        assert_eq!(smile.vol_at(1.0), 0.7);
+        assert_eq!(smile.vol_at(3.0), 0.6);
+
+        assert!(VolSmile::new(vec![]).is_err());
+        assert!(VolSmile::new(vec![(2.0, 0.5), (2.0, 0.6)]).is_err());
+        assert!(VolSmile::new(vec![(-1.0, 0.5)]).is_err());
+        assert!(serde_json::from_str::<VolSmile>("[[2.0, -0.5]]").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::error::EngineError;
+use crate::pricing::{DaysToExpiry, simulate_expiry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Long,
+    Short,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leg {
+    pub strike: f64,
+    pub days: DaysToExpiry,
+    pub is_call: bool,
+    pub quantity: f64,
+    pub side: Side,
+}
+
+impl Leg {
+    pub fn long(strike: f64, days: impl Into<DaysToExpiry>, is_call: bool) -> Self {
+        Self { strike, days: days.into(), is_call, quantity: 1.0, side: Side::Long }
+    }
+
+    pub fn short(strike: f64, days: impl Into<DaysToExpiry>, is_call: bool) -> Self {
+        Self { strike, days: days.into(), is_call, quantity: 1.0, side: Side::Short }
+    }
+
+    pub fn with_quantity(mut self, quantity: f64) -> Self {
+        self.quantity = quantity;
+        self
+    }
+
+    // Positive for long legs, negative for short ones
+    pub fn signed_quantity(&self) -> f64 {
+        match self.side {
+            Side::Long => self.quantity,
+            Side::Short => -self.quantity,
+        }
+    }
+}
+
+// A set of option legs on one underlying, priced together
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Strategy {
+    pub legs: Vec<Leg>,
+}
+
+impl Strategy {
+    pub fn new(legs: Vec<Leg>) -> Self {
+        Self { legs }
+    }
+
+    /// Long call and long put at the same strike.
+    pub fn straddle(strike: f64, days: impl Into<DaysToExpiry>) -> Self {
+        let days = days.into();
+        Self::new(vec![Leg::long(strike, days, true), Leg::long(strike, days, false)])
+    }
+
+    /// Long put below and long call above the money.
+    pub fn strangle(put_strike: f64, call_strike: f64, days: impl Into<DaysToExpiry>) -> Self {
+        let days = days.into();
+        Self::new(vec![Leg::long(put_strike, days, false), Leg::long(call_strike, days, true)])
+    }
+
+    /// Long one strike, short another of the same type; a bull call spread is `long < short` with calls.
+    pub fn vertical_spread(long_strike: f64, short_strike: f64, days: impl Into<DaysToExpiry>, is_call: bool) -> Self {
+        let days = days.into();
+        Self::new(vec![Leg::long(long_strike, days, is_call), Leg::short(short_strike, days, is_call)])
+    }
+
+    /// Short put spread plus short call spread; strikes in ascending order.
+    pub fn iron_condor(put_long: f64, put_short: f64, call_short: f64, call_long: f64, days: impl Into<DaysToExpiry>) -> Self {
+        let days = days.into();
+        Self::new(vec![
+            Leg::long(put_long, days, false),
+            Leg::short(put_short, days, false),
+            Leg::short(call_short, days, true),
+            Leg::long(call_long, days, true),
+        ])
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.legs.is_empty() {
+            return Err(EngineError::InvalidParams("strategy must have at least one leg".to_string()).into());
+        }
+        if let Some(leg) = self.legs.iter().find(|leg| !(leg.quantity.is_finite() && leg.quantity > 0.0)) {
+            return Err(EngineError::InvalidParams(format!("quantity must be a positive number, got {}", leg.quantity)).into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegPremium {
+    pub leg: Leg,
+    pub premium: f64, // per unit
+    pub value: f64,   // premium * signed quantity
+}
+
+// `net_premium` > 0 is a net debit (paid to open), < 0 a net credit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyResult {
+    pub underlying_price: f64,
+    pub net_premium: f64,
+    pub legs: Vec<LegPremium>,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub from_cache: bool,
+}
+
+impl StrategyResult {
+    pub(crate) fn new(underlying_price: f64, legs: Vec<LegPremium>) -> Self {
+        Self {
+            underlying_price,
+            net_premium: legs.iter().map(|leg| leg.value).sum(),
+            legs,
+            timestamp: 0,
+            from_cache: false,
+        }
+    }
+
+    /// Net profit or loss at expiry if the underlying finishes at `terminal_price`, every leg
+    /// opened at its quoted premium.
+    pub fn pnl_at_expiry(&self, terminal_price: f64) -> f64 {
+        self.legs.iter()
+            .map(|leg| {
+                let long = leg.leg.side == Side::Long;
+                simulate_expiry(leg.leg.strike, leg.premium, leg.leg.is_call, long, terminal_price) * leg.leg.quantity
+            })
+            .sum()
+    }
+}
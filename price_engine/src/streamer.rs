@@ -0,0 +1,195 @@
+use futures::stream::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{debug, warn};
+
+use crate::oracle::{PriceData, PriceOracle};
+use crate::retry::is_retryable;
+
+// Ticks buffered per feed, shared by every subscriber. The buffer never grows past this:
+// once full each new tick evicts the oldest, and a subscriber that still wanted an evicted
+// tick resumes at the newest one instead of working through the stale rest
+pub const CHANNEL_CAPACITY: usize = 64;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+type FeedMap = Arc<Mutex<HashMap<String, FeedStream>>>;
+
+struct FeedStream {
+    sender: broadcast::Sender<PriceData>,
+    task: JoinHandle<()>,
+}
+
+// Fans one upstream oracle stream per symbol out to any number of subscribers.
+// The upstream task is started by the first subscriber and aborted when the last one drops.
+pub struct PriceStreamer {
+    oracle: Arc<dyn PriceOracle>,
+    feeds: FeedMap,
+}
+
+pub struct PriceSubscription {
+    symbol: String,
+    receiver: broadcast::Receiver<PriceData>,
+    feeds: FeedMap,
+}
+
+impl PriceStreamer {
+    pub fn new(oracle: Arc<dyn PriceOracle>) -> Self {
+        Self {
+            oracle,
+            feeds: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn subscribe(&self, symbol: &str) -> PriceSubscription {
+        let mut feeds = self.feeds.lock().unwrap();
+        let feed = feeds.entry(symbol.to_string()).or_insert_with(|| {
+            let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+            let task = tokio::spawn(run_feed(self.oracle.clone(), symbol.to_string(), sender.clone()));
+            FeedStream { sender, task }
+        });
+
+        PriceSubscription {
+            symbol: symbol.to_string(),
+            receiver: feed.sender.subscribe(),
+            feeds: self.feeds.clone(),
+        }
+    }
+
+    /// Number of symbols with a live upstream stream.
+    pub fn active_feeds(&self) -> usize {
+        self.feeds.lock().unwrap().len()
+    }
+}
+
+impl PriceSubscription {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Next tick for the feed, in order while the subscriber keeps up. One that fell more than
+    /// `CHANNEL_CAPACITY` ticks behind gets the newest tick next and loses everything before it.
+    pub async fn recv(&mut self) -> Option<PriceData> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(price) => return Some(price),
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(symbol = self.symbol, skipped, "Subscriber lagged, skipping to the newest tick");
+                    if let Some(price) = self.newest() {
+                        return Some(price);
+                    }
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Ticks buffered for this subscriber and not yet received, at most `CHANNEL_CAPACITY`.
+    pub fn backlog(&self) -> usize {
+        // The receiver also counts ticks already evicted from the buffer
+        self.receiver.len().min(CHANNEL_CAPACITY)
+    }
+
+    // Drains the buffer, keeping only its last tick
+    fn newest(&mut self) -> Option<PriceData> {
+        let mut newest = None;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(price) => newest = Some(price),
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return newest,
+            }
+        }
+    }
+}
+
+impl Drop for PriceSubscription {
+    fn drop(&mut self) {
+        let mut feeds = self.feeds.lock().unwrap();
+        // Our own receiver is still alive here, so a count of 1 means we are the last subscriber
+        let is_last = feeds
+            .get(&self.symbol)
+            .is_some_and(|feed| feed.sender.receiver_count() <= 1);
+        if is_last && let Some(feed) = feeds.remove(&self.symbol) {
+            feed.task.abort();
+        }
+    }
+}
+
+async fn run_feed(oracle: Arc<dyn PriceOracle>, symbol: String, sender: broadcast::Sender<PriceData>) {
+    loop {
+        match oracle.stream_prices(&symbol).await {
+            Ok(stream) => {
+                debug!(symbol, "Price stream opened");
+                let mut stream = std::pin::pin!(stream);
+                while let Some(update) = stream.next().await {
+                    match update {
+                        Ok(price) => {
+                            // No receivers is fine, the subscription that started us may be mid-drop
+                            let _ = sender.send(price);
+                        }
+                        Err(err) => warn!(symbol, error = ?err, "Error receiving price update"),
+                    }
+                }
+            }
+            Err(err) if !is_retryable(&err) => {
+                // Dropping the sender closes every subscription for this symbol
+                warn!(symbol, error = %format!("{:#}", err), "Price stream unavailable");
+                return;
+            }
+            Err(err) => warn!(symbol, error = ?err, "Failed to open price stream"),
+        }
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_oracle::MockOracle;
+    use crate::oracle::PythOracle;
+
+    #[tokio::test]
+    async fn test_subscribers_share_one_feed_until_last_drops() {
+        let streamer = PriceStreamer::new(Arc::new(PythOracle::new()));
+
+        let first = streamer.subscribe("SUI");
+        let second = streamer.subscribe("SUI");
+        assert_eq!(streamer.active_feeds(), 1);
+
+        drop(first);
+        assert_eq!(streamer.active_feeds(), 1);
+
+        drop(second);
+        assert_eq!(streamer.active_feeds(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_skips_to_newest_tick() {
+        let oracle = Arc::new(MockOracle::new().with_price("SUI", 2.0, 0.5));
+        let streamer = PriceStreamer::new(oracle.clone());
+        let mut fast = streamer.subscribe("SUI");
+        let mut slow = streamer.subscribe("SUI");
+        while oracle.stream_subscribers("SUI") == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let ticks = 3 * CHANNEL_CAPACITY;
+        for i in 0..ticks {
+            oracle.push_price("SUI", i as f64);
+            // Only the fast subscriber reads; waiting on it paces the pushes through the feed
+            assert_eq!(fast.recv().await.unwrap().price, i as f64);
+            assert!(slow.backlog() <= CHANNEL_CAPACITY);
+        }
+
+        assert_eq!(slow.backlog(), CHANNEL_CAPACITY);
+        assert_eq!(slow.recv().await.unwrap().price, (ticks - 1) as f64);
+        assert_eq!(slow.backlog(), 0);
+        // Back in step, it gets every tick again
+        oracle.push_price("SUI", 1000.0);
+        assert_eq!(slow.recv().await.unwrap().price, 1000.0);
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{at_strike, price_strikes_on};
+use crate::pricing::{BlackScholes, DaysToExpiry, Greeks, OptionParams};
+use crate::quote::GreeksRounding;
+use crate::rates::RateCurve;
+use crate::smile::VolSmile;
+
+// Premium grid for one underlying snapshot: rows are expiries, columns are strikes,
+// so `calls[i][j]` is the call expiring in `expiries[i]` days struck at `strikes[j]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Surface {
+    pub symbol: String,
+    pub underlying_price: f64,
+    pub volatility: f64,
+    pub expiries: Vec<DaysToExpiry>,
+    // Days each row was priced to: to the listed expiry, after the time-to-expiry floor
+    #[serde(default)]
+    pub times_to_expiry: Vec<f64>,
+    pub strikes: Vec<f64>,
+    pub calls: Vec<Vec<f64>>,
+    pub puts: Vec<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_greeks: Option<Vec<Vec<Greeks>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub put_greeks: Option<Vec<Vec<Greeks>>>,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub from_cache: bool,
+}
+
+impl Surface {
+    // Prices every grid point from `base`, which carries the underlying, vol and rate, the
+    // row for `expiries[i]` to `times[i]` days; a smile overrides the vol strike by strike,
+    // a rate curve the rate expiry by expiry
+    pub(crate) fn price(
+        base: OptionParams,
+        expiries: &[DaysToExpiry],
+        times: &[f64],
+        strikes: &[f64],
+        smile: Option<&VolSmile>,
+        rates: Option<&RateCurve>,
+        with_greeks: bool,
+    ) -> Result<Self> {
+        let row_params = |time_to_expiry: f64, is_call: bool| OptionParams {
+            time_to_expiry,
+            risk_free_rate: rates.map_or(base.risk_free_rate, |curve| curve.rate_at(time_to_expiry / base.day_count.days_per_year())),
+            is_call,
+            ..base.clone()
+        };
+        let greeks_grid = |is_call: bool| -> Result<Vec<Vec<Greeks>>> {
+            times.iter()
+                .map(|&time| {
+                    strikes.iter()
+                        .map(|&strike| BlackScholes::calculate_greeks(&at_strike(&row_params(time, is_call), strike, smile)))
+                        .collect()
+                })
+                .collect()
+        };
+        let premium_grid = |is_call: bool| -> Result<Vec<Vec<f64>>> {
+            times.iter().map(|&time| price_strikes_on(&row_params(time, is_call), strikes, smile)).collect()
+        };
+
+        Ok(Self {
+            symbol: String::new(),
+            underlying_price: base.underlying_price,
+            volatility: base.volatility,
+            expiries: expiries.to_vec(),
+            times_to_expiry: times.to_vec(),
+            strikes: strikes.to_vec(),
+            calls: premium_grid(true)?,
+            puts: premium_grid(false)?,
+            call_greeks: with_greeks.then(|| greeks_grid(true)).transpose()?,
+            put_greeks: with_greeks.then(|| greeks_grid(false)).transpose()?,
+            timestamp: 0,
+            from_cache: false,
+        })
+    }
+
+    /// Premium at an exact grid point, if `expiry` and `strike` are on the grid.
+    pub fn premium(&self, expiry: impl Into<DaysToExpiry>, strike: f64, is_call: bool) -> Option<f64> {
+        let expiry = expiry.into();
+        let row = self.expiries.iter().position(|&days| days == expiry)?;
+        let col = self.strikes.iter().position(|&k| k == strike)?;
+        let grid = if is_call { &self.calls } else { &self.puts };
+        Some(grid[row][col])
+    }
+
+    /// Rounds the Greeks grids, if any, by `rounding`, for a surface that is about to be served.
+    pub fn round_greeks(&mut self, rounding: &GreeksRounding) {
+        for grid in [&mut self.call_greeks, &mut self.put_greeks].into_iter().flatten() {
+            for greeks in grid.iter_mut().flatten() {
+                *greeks = rounding.apply(*greeks);
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use pyth_hermes_client::{ParsedPriceUpdate, PriceUpdate};
+
+// Pyth accumulator updates start with "PNAU"; `update_type` 0 is a Wormhole Merkle update
+const ACCUMULATOR_MAGIC: &[u8; 4] = b"PNAU";
+const WORMHOLE_MERKLE: u8 = 0;
+const PRICE_FEED_MESSAGE: u8 = 0;
+const MERKLE_NODE_LEN: usize = 20;
+
+// One price feed message out of an update's binary payload, the bytes the VAA's Merkle root signs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PriceMessage {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+// Checks every parsed price in `update` against the price message for the same feed in its
+// binary payload, so a relay can't hand us a parsed price other than the one it would post
+// on-chain. The Wormhole guardian signatures and Merkle proofs are left to the Pyth contract
+// that receives the payload; this only makes sure we price off what it would see.
+pub(crate) fn verify_update(update: &PriceUpdate) -> Result<(), String> {
+    let parsed = update.parsed.as_deref().unwrap_or_default();
+    if parsed.is_empty() {
+        return Ok(());
+    }
+    let payloads = update.binary.decode().map_err(|err| err.to_string())?;
+    let mut messages = Vec::new();
+    for payload in &payloads {
+        messages.extend(price_messages(payload)?);
+    }
+    for feed in parsed {
+        let message = messages.iter()
+            .find(|message| same_id(&message.feed_id, &feed.id))
+            .ok_or_else(|| format!("no price message for feed {} in the binary update", feed.id))?;
+        if !matches_parsed(message, feed) {
+            return Err(format!("parsed price for feed {} doesn't match the binary update", feed.id));
+        }
+    }
+    Ok(())
+}
+
+fn same_id(feed_id: &[u8; 32], parsed_id: &str) -> bool {
+    let hex: String = feed_id.iter().map(|byte| format!("{:02x}", byte)).collect();
+    parsed_id.trim_start_matches("0x").eq_ignore_ascii_case(&hex)
+}
+
+fn matches_parsed(message: &PriceMessage, feed: &ParsedPriceUpdate) -> bool {
+    message.price == feed.price.price
+        && message.conf == feed.price.conf
+        && message.expo == feed.price.expo
+        && message.publish_time == feed.price.publish_time
+        && message.ema_price == feed.ema_price.price
+        && message.ema_conf == feed.ema_price.conf
+}
+
+// The price feed messages in one accumulator update; other message types are skipped
+pub(crate) fn price_messages(payload: &[u8]) -> Result<Vec<PriceMessage>, String> {
+    let mut reader = Reader(payload);
+    if reader.take(4)? != ACCUMULATOR_MAGIC {
+        return Err("binary update is not a Pyth accumulator update".to_string());
+    }
+    let _major = reader.u8()?;
+    let _minor = reader.u8()?;
+    let trailing_header = reader.u8()? as usize;
+    reader.take(trailing_header)?;
+    if reader.u8()? != WORMHOLE_MERKLE {
+        return Err("unsupported accumulator update type".to_string());
+    }
+    let vaa_len = reader.u16()? as usize;
+    reader.take(vaa_len)?;
+
+    let mut messages = Vec::new();
+    for _ in 0..reader.u8()? {
+        let message_len = reader.u16()? as usize;
+        let message = reader.take(message_len)?;
+        let proof_len = reader.u8()? as usize;
+        reader.take(proof_len * MERKLE_NODE_LEN)?;
+        if message.first() == Some(&PRICE_FEED_MESSAGE) {
+            messages.push(price_message(&message[1..])?);
+        }
+    }
+    Ok(messages)
+}
+
+fn price_message(body: &[u8]) -> Result<PriceMessage, String> {
+    let mut reader = Reader(body);
+    let feed_id = reader.take(32)?.try_into().expect("took 32 bytes");
+    let price = reader.u64()? as i64;
+    let conf = reader.u64()?;
+    let expo = reader.u32()? as i32;
+    let publish_time = reader.u64()? as i64;
+    let _prev_publish_time = reader.u64()?;
+    let ema_price = reader.u64()? as i64;
+    let ema_conf = reader.u64()?;
+    Ok(PriceMessage { feed_id, price, conf, expo, publish_time, ema_price, ema_conf })
+}
+
+// Big-endian reads off the front of a byte slice
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < len {
+            return Err("binary update is truncated".to_string());
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().expect("took 2 bytes")))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().expect("took 4 bytes")))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().expect("took 8 bytes")))
+    }
+}
+
+// An accumulator update carrying `messages`, with an empty VAA and no proofs
+#[cfg(test)]
+pub(crate) fn encode_update(messages: &[PriceMessage]) -> Vec<u8> {
+    let mut payload = ACCUMULATOR_MAGIC.to_vec();
+    payload.extend([1, 0, 0, WORMHOLE_MERKLE]);
+    payload.extend(0u16.to_be_bytes());
+    payload.push(messages.len() as u8);
+    for message in messages {
+        let mut body = vec![PRICE_FEED_MESSAGE];
+        body.extend(message.feed_id);
+        body.extend(message.price.to_be_bytes());
+        body.extend(message.conf.to_be_bytes());
+        body.extend(message.expo.to_be_bytes());
+        body.extend(message.publish_time.to_be_bytes());
+        body.extend((message.publish_time - 1).to_be_bytes());
+        body.extend(message.ema_price.to_be_bytes());
+        body.extend(message.ema_conf.to_be_bytes());
+        payload.extend((body.len() as u16).to_be_bytes());
+        payload.extend(body);
+        payload.push(0);
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> PriceMessage {
+        PriceMessage {
+            feed_id: [0x23; 32],
+            price: 2_500,
+            conf: 10,
+            expo: -3,
+            publish_time: 1_700_000_000,
+            ema_price: 2_400,
+            ema_conf: 8,
+        }
+    }
+
+    fn update(payload: &[u8], price: &str) -> PriceUpdate {
+        let hex: String = payload.iter().map(|byte| format!("{:02x}", byte)).collect();
+        serde_json::from_value(serde_json::json!({
+            "binary": { "encoding": "hex", "data": [hex] },
+            "parsed": [{
+                "id": "23".repeat(32),
+                "price": { "price": price, "conf": "10", "expo": -3, "publish_time": 1_700_000_000 },
+                "ema_price": { "price": "2400", "conf": "8", "expo": -3, "publish_time": 1_700_000_000 },
+                "metadata": {},
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parsed_prices_must_match_the_binary_update() {
+        let payload = encode_update(&[message()]);
+        assert_eq!(price_messages(&payload).unwrap(), vec![message()]);
+        assert!(verify_update(&update(&payload, "2500")).is_ok());
+
+        let err = verify_update(&update(&payload, "2600")).unwrap_err();
+        assert!(err.contains("doesn't match"), "{}", err);
+        let other_feed = encode_update(&[PriceMessage { feed_id: [0x44; 32], ..message() }]);
+        assert!(verify_update(&update(&other_feed, "2500")).unwrap_err().contains("no price message"));
+        assert!(verify_update(&update(&payload[..payload.len() - 5], "2500")).unwrap_err().contains("truncated"));
+        assert!(verify_update(&update(b"nope", "2500")).is_err());
+    }
+}